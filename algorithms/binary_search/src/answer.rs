@@ -0,0 +1,144 @@
+use std::ops::Range;
+
+// Integer type which search_min can search over.
+pub trait SearchInt: Copy + Ord {
+    fn next(self) -> Self;
+
+    // Average of low and high rounded down. Unlike (low + high) / 2 it can't overflow,
+    // and unlike low + (high - low) / 2 it works for signed ranges wider than the type's max.
+    fn mid(low: Self, high: Self) -> Self;
+}
+
+macro_rules! impl_search_int {
+    ($($t:ty),*) => {
+        $(
+            impl SearchInt for $t {
+                fn next(self) -> Self {
+                    self + 1
+                }
+
+                fn mid(low: Self, high: Self) -> Self {
+                    // Common bits plus half of different ones; shift of signed is arithmetic.
+                    (low & high) + ((low ^ high) >> 1)
+                }
+            }
+        )*
+    };
+}
+
+impl_search_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+// Smallest x in range for which pred holds, None if it holds for none.
+// pred has to be monotone: false for all x before the answer and true after it.
+pub fn search_min<T, P>(range: Range<T>, mut pred: P) -> Option<T>
+where
+    T: SearchInt,
+    P: FnMut(T) -> bool,
+{
+    // Answer is in [low, high], high is range.end if there is none.
+    let mut low = range.start;
+    let mut high = range.end;
+    while low < high {
+        // mid < high, so mid + 1 can't overflow.
+        let mid = T::mid(low, high);
+        if pred(mid) {
+            high = mid
+        } else {
+            low = mid.next()
+        }
+    }
+    (low < range.end).then_some(low)
+}
+
+// Bisection: x in [lo, hi] for which pred holds and which is less than eps above
+// the smallest such x; None if pred doesn't hold even for hi.
+// pred has to be monotone like in search_min.
+pub fn search_float<P>(mut lo: f64, mut hi: f64, eps: f64, mut pred: P) -> Option<f64>
+where
+    P: FnMut(f64) -> bool,
+{
+    if !pred(hi) {
+        return None;
+    }
+    while hi - lo > eps {
+        let mid = lo + (hi - lo) / 2.0;
+        // Neighbour floats: eps is smaller than precision, so range can't get narrower.
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        if pred(mid) {
+            hi = mid
+        } else {
+            lo = mid
+        }
+    }
+    Some(hi)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::answer::*;
+
+    #[test]
+    fn test_search_min() -> Result<(), Box<dyn std::error::Error>> {
+        for end in 0..40u32 {
+            for answer in 0..=end {
+                let res = search_min(0..end, |x| x >= answer);
+                let expected = if answer < end { Some(answer) } else { None };
+                if res != expected {
+                    return Err(format!("0..{} >= {} -> {:?}", end, answer, res).into());
+                }
+            }
+        }
+        assert_eq!(search_min(5..5, |_: u8| true), None);
+        assert_eq!(search_min(-50..50, |x: i8| x >= -10), Some(-10));
+
+        // Ranges as wide as the type: midpoint of min and max doesn't overflow.
+        assert_eq!(search_min(i64::MIN..i64::MAX, |x| x >= -3), Some(-3));
+        assert_eq!(search_min(i64::MIN..i64::MAX, |_| true), Some(i64::MIN));
+        assert_eq!(search_min(0..u128::MAX, |x| x >= u128::MAX - 1), Some(u128::MAX - 1));
+        assert_eq!(
+            search_min(0..u64::MAX, |x| x.checked_mul(x).is_none_or(|s| s >= 1 << 62)),
+            Some(1 << 31)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_min_capacity() {
+        // Smallest capacity to ship packages in order within days.
+        let weights: Vec<u64> = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        let fits = |capacity: u64, days: usize| {
+            let mut used: usize = 1;
+            let mut load: u64 = 0;
+            for &w in &weights {
+                if w > capacity {
+                    return false;
+                }
+                if load + w > capacity {
+                    used += 1;
+                    load = 0;
+                }
+                load += w;
+            }
+            used <= days
+        };
+        let total: u64 = weights.iter().sum();
+        assert_eq!(search_min(0..total + 1, |c| fits(c, 5)), Some(15));
+        assert_eq!(search_min(0..total + 1, |c| fits(c, 1)), Some(55));
+        assert_eq!(search_min(0..total + 1, |c| fits(c, 10)), Some(10));
+    }
+
+    #[test]
+    fn test_search_float() {
+        let sqrt2 = search_float(0.0, 2.0, 1e-9, |x| x * x >= 2.0).unwrap();
+        assert!(sqrt2 >= 2f64.sqrt() && sqrt2 - 2f64.sqrt() <= 1e-9);
+        let cbrt = search_float(-10.0, 10.0, 1e-6, |x| x * x * x >= -27.0).unwrap();
+        assert!((cbrt + 3.0).abs() <= 1e-6);
+        assert_eq!(search_float(0.0, 1.0, 1e-3, |x| x > 1.0), None);
+        assert_eq!(search_float(0.0, 1.0, 1e-3, |_| true), Some(0.0009765625));
+        // eps below precision stops at neighbour floats instead of looping forever.
+        let one = search_float(0.0, 2.0, 0.0, |x| x >= 1.0).unwrap();
+        assert_eq!(one, 1.0);
+    }
+}
@@ -0,0 +1,99 @@
+use std::cmp::Ordering;
+
+use crate::SearchInt;
+
+// Same result as binary_search, but takes O(log i) probes where i is the answer,
+// so it's faster for targets near the beginning.
+pub fn exponential_search<T>(v: &[T], target: &T) -> Result<usize, usize>
+where
+    T: Ord,
+{
+    exponential_search_by(|i| v.get(i).map(|x| x.cmp(target)))
+}
+
+// Galloping search over source which length isn't known in advance.
+// probe(i) is ordering of i-th element compared to target, None if there is no i-th element;
+// elements have to be sorted and None can't be followed by Some.
+// If element at usize::MAX is still less than target, search stops with Err(usize::MAX),
+// though the real insertion point is after it.
+pub fn exponential_search_by<F>(mut probe: F) -> Result<usize, usize>
+where
+    F: FnMut(usize) -> Option<Ordering>,
+{
+    // Indices 0, 1, 3, 7, ... are probed until the first one which isn't less than target,
+    // then the answer is in (previous, high] range.
+    let mut low: usize = 0;
+    let mut high: usize = 0;
+    let mut at_high = probe(high);
+    while at_high == Some(Ordering::Less) {
+        if high == usize::MAX {
+            return Err(usize::MAX);
+        }
+        low = high + 1;
+        high = high.saturating_mul(2).saturating_add(1);
+        at_high = probe(high);
+    }
+    // Element at high is never less than target, the ones before low are.
+    while low < high {
+        let mid = usize::mid(low, high);
+        match probe(mid) {
+            Some(Ordering::Less) => low = mid + 1,
+            Some(Ordering::Equal) => return Ok(mid),
+            // The end of source is after all elements.
+            at_mid => {
+                high = mid;
+                at_high = at_mid
+            }
+        }
+    }
+    match at_high {
+        Some(Ordering::Equal) => Ok(high),
+        _ => Err(high),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::exponential::*;
+    use crate::tests::{gen_cases, gen_v};
+
+    #[test]
+    fn test_exponential_search() -> Result<(), Box<dyn std::error::Error>> {
+        for case in gen_cases() {
+            let res = exponential_search(&case.0, &case.1);
+            if res != case.2 {
+                return Err(format!("{:?} -> {:?}", case, res).into());
+            }
+        }
+        for len in 0..=64 {
+            let v = gen_v(len);
+            for target in 0..=len as u128 + 1 {
+                assert_eq!(exponential_search(&v, &target), v.binary_search(&target));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_exponential_search_unbounded() {
+        // Squares of all numbers, without end.
+        let squares =
+            |target: u128| exponential_search_by(|i| Some((i as u128 * i as u128).cmp(&target)));
+        assert_eq!(squares(0), Ok(0));
+        assert_eq!(squares(144), Ok(12));
+        assert_eq!(squares(145), Err(13));
+        assert_eq!(squares(1 << 60), Ok(1 << 30));
+
+        // Source without end which is less than target everywhere.
+        let mut probes: usize = 0;
+        let res = exponential_search_by(|_| {
+            probes += 1;
+            Some(Ordering::Less)
+        });
+        assert_eq!(res, Err(usize::MAX));
+        assert_eq!(probes, usize::BITS as usize + 1);
+        // Target right at the last index.
+        let res = exponential_search_by(|i| Some(i.cmp(&usize::MAX)));
+        assert_eq!(res, Ok(usize::MAX));
+    }
+}
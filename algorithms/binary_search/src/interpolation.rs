@@ -0,0 +1,92 @@
+use std::cmp::Ordering;
+
+use crate::SearchInt;
+
+// Same result as binary_search, but the next probe is guessed by the target's position
+// between the first and the last key, so it takes O(log log n) probes for uniformly
+// distributed keys and up to O(n) for skewed ones.
+pub fn interpolation_search<T>(v: &[T], target: T) -> Result<usize, usize>
+where
+    T: Copy + Into<u128>,
+{
+    interpolation_search_by_key(v, target.into(), |&x| x.into())
+}
+
+// v has to be sorted by key.
+pub fn interpolation_search_by_key<T, F>(v: &[T], target: u128, mut key: F) -> Result<usize, usize>
+where
+    F: FnMut(&T) -> u128,
+{
+    // Target can only be in [low, high) range.
+    let mut low: usize = 0;
+    let mut high: usize = v.len();
+    while low < high {
+        let (first, last) = (key(&v[low]), key(&v[high - 1]));
+        if target < first {
+            return Err(low);
+        }
+        if target > last {
+            return Err(high);
+        }
+        // first <= target <= last, so pos is in [low, high).
+        let pos = if first == last {
+            low
+        } else {
+            // Product can overflow for keys far apart, then bisection is used for this step.
+            match (target - first).checked_mul((high - 1 - low) as u128) {
+                Some(product) => low + (product / (last - first)) as usize,
+                None => usize::mid(low, high),
+            }
+        };
+        match key(&v[pos]).cmp(&target) {
+            Ordering::Equal => return Ok(pos),
+            Ordering::Less => low = pos + 1,
+            Ordering::Greater => high = pos,
+        }
+    }
+    Err(low)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpolation::*;
+    use crate::tests::{gen_cases, gen_v};
+
+    #[test]
+    fn test_interpolation_search() -> Result<(), Box<dyn std::error::Error>> {
+        for case in gen_cases() {
+            let res = interpolation_search(&case.0, case.1);
+            if res != case.2 {
+                return Err(format!("{:?} -> {:?}", case, res).into());
+            }
+        }
+        for len in 0..=64 {
+            let v = gen_v(len);
+            // Squares are skewed, and some keys repeat.
+            let skewed: Vec<u128> = (0..len).map(|i| (i as u128 / 2).pow(2)).collect();
+            for target in 0..=(len * len) as u128 {
+                assert_eq!(interpolation_search(&v, target), v.binary_search(&target));
+                let res = interpolation_search(&skewed, target);
+                match res {
+                    Ok(i) => assert_eq!(skewed[i], target),
+                    Err(i) => assert_eq!(Err(i), skewed.binary_search(&target)),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_interpolation_search_extremes() {
+        // Keys so far apart that interpolation would overflow.
+        let v: Vec<u128> = vec![0, 1, 2, u128::MAX - 1, u128::MAX];
+        for (i, x) in v.iter().enumerate() {
+            assert_eq!(interpolation_search(&v, *x), Ok(i));
+        }
+        assert_eq!(interpolation_search(&v, u128::MAX / 2), Err(3));
+
+        let words = vec!["a", "bb", "ccc", "dddd"];
+        assert_eq!(interpolation_search_by_key(&words, 3, |w| w.len() as u128), Ok(2));
+        assert_eq!(interpolation_search_by_key(&words, 5, |w| w.len() as u128), Err(4));
+    }
+}
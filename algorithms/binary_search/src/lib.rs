@@ -1,55 +1,141 @@
-fn binary_search(v: Vec<u128>, target: u128) -> Option<usize> {
-    let mut low_index = 0;
-    let mut high_index = v.len() - 1;
-    while low_index <= high_index {
-        let mid_index = (low_index + high_index) / 2;
-        let guess = v[mid_index];
-        // println!("{:?}", (low_index, high_index, mid_index, guess));
-        if guess == target {
-            return Some(mid_index);
-        }
-        if guess < target {
-            low_index = mid_index + 1
-        }
-        if guess > target {
-            match mid_index {
-                0 => return None,
-                _ => high_index = mid_index - 1,
-            }
+use std::cmp::Ordering;
+use std::ops::Range;
+
+mod answer;
+mod exponential;
+mod interpolation;
+mod ternary;
+
+pub use answer::{search_float, search_min, SearchInt};
+pub use exponential::{exponential_search, exponential_search_by};
+pub use interpolation::{interpolation_search, interpolation_search_by_key};
+pub use ternary::{ternary_search_float, ternary_search_max};
+
+// Ok with index of some element equal to target, or Err with index where target can be
+// inserted keeping v sorted, like slice::binary_search.
+pub fn binary_search<T>(v: &[T], target: &T) -> Result<usize, usize>
+where
+    T: Ord,
+{
+    // Target can only be in [low, high) range.
+    let mut low: usize = 0;
+    let mut high: usize = v.len();
+    while low < high {
+        let mid = usize::mid(low, high);
+        match v[mid].cmp(target) {
+            Ordering::Equal => return Ok(mid),
+            Ordering::Less => low = mid + 1,
+            Ordering::Greater => high = mid,
         }
     }
-    None
+    Err(low)
 }
 
-fn binary_search_recursive(v: Vec<u128>, target: u128, start: usize, stop: usize) -> Option<usize> {
-    let mut mid: usize = 0;
-    if v.len() > 1 {
-        mid = stop.checked_sub(start)?.checked_div(2)? + start
-    }
-    if v[mid] == target {
-        return Some(mid);
+// Same as binary_search, but searches the half with target by recursive call on subslice.
+pub fn binary_search_recursive<T>(v: &[T], target: &T) -> Result<usize, usize>
+where
+    T: Ord,
+{
+    if v.is_empty() {
+        return Err(0);
     }
-    if v[mid] < target {
-        return binary_search_recursive(v, target, mid + 1, stop);
+    let mid = v.len() / 2;
+    match v[mid].cmp(target) {
+        Ordering::Equal => Ok(mid),
+        // Indices in the right half are shifted by its start.
+        Ordering::Less => match binary_search_recursive(&v[mid + 1..], target) {
+            Ok(i) => Ok(mid + 1 + i),
+            Err(i) => Err(mid + 1 + i),
+        },
+        Ordering::Greater => binary_search_recursive(&v[..mid], target),
     }
-    if v[mid] > target {
-        return binary_search_recursive(v.to_vec(), target, start, mid.checked_sub(1)?);
+}
+
+// Index of the first element for which pred is false, v.len() if there is none.
+// v has to be partitioned: pred is true for all elements before the point and false after it.
+pub fn partition_point<T, P>(v: &[T], mut pred: P) -> usize
+where
+    P: FnMut(&T) -> bool,
+{
+    // Answer is in [low, high].
+    let mut low: usize = 0;
+    let mut high: usize = v.len();
+    while low < high {
+        let mid = usize::mid(low, high);
+        if pred(&v[mid]) {
+            low = mid + 1
+        } else {
+            high = mid
+        }
     }
-    None
+    low
+}
+
+// Index of the first element not less than target, so target can be inserted there
+// before all equal elements.
+pub fn lower_bound<T>(v: &[T], target: &T) -> usize
+where
+    T: Ord,
+{
+    lower_bound_by(v, target, T::cmp)
+}
+
+// v has to be sorted by cmp.
+pub fn lower_bound_by<T, F>(v: &[T], target: &T, mut cmp: F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    partition_point(v, |x| cmp(x, target) == Ordering::Less)
+}
+
+// Index of the first element greater than target, so target can be inserted there
+// after all equal elements.
+pub fn upper_bound<T>(v: &[T], target: &T) -> usize
+where
+    T: Ord,
+{
+    upper_bound_by(v, target, T::cmp)
+}
+
+pub fn upper_bound_by<T, F>(v: &[T], target: &T, mut cmp: F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    partition_point(v, |x| cmp(x, target) != Ordering::Greater)
+}
+
+// Range of elements equal to target; it's empty and starts at insertion point if there are none.
+pub fn equal_range<T>(v: &[T], target: &T) -> Range<usize>
+where
+    T: Ord,
+{
+    equal_range_by(v, target, T::cmp)
+}
+
+pub fn equal_range_by<T, F>(v: &[T], target: &T, mut cmp: F) -> Range<usize>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let start = lower_bound_by(v, target, &mut cmp);
+    // Upper bound can't be before lower bound, so only the rest is searched.
+    let end = start + upper_bound_by(&v[start..], target, &mut cmp);
+    start..end
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{binary_search, binary_search_recursive};
+    use crate::{
+        binary_search, binary_search_recursive, equal_range, equal_range_by, exponential_search_by,
+        interpolation_search_by_key, lower_bound, lower_bound_by, partition_point, upper_bound,
+        upper_bound_by,
+    };
 
     #[test]
     fn test_binary_search() -> Result<(), Box<dyn std::error::Error>> {
-        let cases = gen_cases();
-        for case in cases {
-            let keep = case.clone();
-            let res_index = binary_search(case.0, case.1);
+        for case in gen_cases() {
+            let res_index = binary_search(&case.0, &case.1);
             if res_index != case.2 {
-                return Err(format!("{:?} -> {:?}", keep, res_index).to_string().into());
+                return Err(format!("{:?} -> {:?}", case, res_index).into());
             }
         }
         Ok(())
@@ -57,35 +143,203 @@ mod tests {
 
     #[test]
     fn test_binary_search_recursive() -> Result<(), Box<dyn std::error::Error>> {
-        let cases = gen_cases();
-        for case in cases {
-            let keep = case.clone();
-            let length = case.0.len();
-            let res_index = binary_search_recursive(case.0, case.1, 0, length - 1);
+        for case in gen_cases() {
+            let res_index = binary_search_recursive(&case.0, &case.1);
             if res_index != case.2 {
-                return Err(format!("{:?} -> {:?}", keep, res_index).to_string().into());
+                return Err(format!("{:?} -> {:?}", case, res_index).into());
             }
         }
         Ok(())
     }
 
-    fn gen_cases() -> Vec<(Vec<u128>, u128, Option<usize>)> {
+    #[test]
+    fn test_binary_search_exhaustive() -> Result<(), Box<dyn std::error::Error>> {
+        type Search = fn(&[u128], &u128) -> Result<usize, usize>;
+        let searches: Vec<(&str, Search)> = vec![
+            ("binary_search", binary_search),
+            ("binary_search_recursive", binary_search_recursive),
+        ];
+        for len in 0..=64 {
+            // Odd targets are between elements, the last one is after all of them.
+            let distinct: Vec<u128> = (0..len).map(|i| 2 * i as u128 + 1).collect();
+            let duplicates: Vec<u128> = (0..len).map(|i| i as u128 / 3).collect();
+            for v in [distinct, duplicates] {
+                for target in 0..=2 * len as u128 + 1 {
+                    for (name, search) in &searches {
+                        let res = search(&v, &target);
+                        let ok = match res {
+                            Ok(i) => v[i] == target,
+                            Err(i) => {
+                                i == v.partition_point(|&x| x < target) && !v.contains(&target)
+                            }
+                        };
+                        if !ok {
+                            return Err(format!("{}: {:?} {} -> {:?}", name, v, target, res).into());
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_bounds() -> Result<(), Box<dyn std::error::Error>> {
+        let cases: Vec<Vec<u128>> = vec![
+            vec![],
+            vec![5],
+            vec![1, 2, 2, 2, 3, 7, 7, 9],
+            vec![4, 4, 4, 4],
+            gen_v(100),
+        ];
+        for v in cases {
+            for target in 0..=(v.last().copied().unwrap_or(0) + 1) {
+                let lower = v.iter().take_while(|&&x| x < target).count();
+                let upper = v.iter().take_while(|&&x| x <= target).count();
+                let res = (lower_bound(&v, &target), upper_bound(&v, &target));
+                if res != (lower, upper) {
+                    return Err(format!("{:?} {} -> {:?}", v, target, res).into());
+                }
+                if equal_range(&v, &target) != (lower..upper) {
+                    return Err(format!("{:?} {} -> bad range", v, target).into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_bounds_by() {
+        // Sorted descending.
+        let v: Vec<u128> = vec![9, 7, 7, 3, 2, 2, 1];
+        let desc = |a: &u128, b: &u128| b.cmp(a);
+        assert_eq!(lower_bound_by(&v, &7, desc), 1);
+        assert_eq!(upper_bound_by(&v, &7, desc), 3);
+        assert_eq!(equal_range_by(&v, &2, desc), 4..6);
+        assert_eq!(equal_range_by(&v, &5, desc), 3..3);
+        assert_eq!(equal_range_by(&v, &0, desc), 7..7);
+
+        let words = vec!["apple", "Banana", "cherry", "CHERRY", "fig"];
+        let ignore_case = |a: &&str, b: &&str| a.to_lowercase().cmp(&b.to_lowercase());
+        assert_eq!(equal_range_by(&words, &"Cherry", ignore_case), 2..4);
+        assert_eq!(lower_bound_by(&words, &"date", ignore_case), 4);
+    }
+
+    #[test]
+    fn test_partition_point() {
+        // Log records sorted by timestamp.
+        let logs: Vec<(u64, &str)> = vec![
+            (100, "start"),
+            (105, "connect"),
+            (105, "login"),
+            (230, "query"),
+            (231, "query"),
+            (400, "logout"),
+        ];
+        let between = |from: u64, to: u64| -> Vec<&str> {
+            let start = partition_point(&logs, |l| l.0 < from);
+            let end = partition_point(&logs, |l| l.0 <= to);
+            logs[start..end].iter().map(|l| l.1).collect()
+        };
+        assert_eq!(between(105, 230), vec!["connect", "login", "query"]);
+        assert_eq!(between(0, 99), Vec::<&str>::new());
+        assert_eq!(between(232, 399), Vec::<&str>::new());
+        assert_eq!(between(0, u64::MAX), logs.iter().map(|l| l.1).collect::<Vec<&str>>());
+
+        let v: Vec<u128> = gen_v(1000);
+        for point in [0, 1, 500, 999, 1000] {
+            assert_eq!(partition_point(&v, |&x| x <= point as u128), point);
+        }
+        assert_eq!(partition_point(&[] as &[u128], |_| true), 0);
+        assert_eq!(lower_bound(&v, &0), 0);
+        assert_eq!(upper_bound_by(&v, &u128::MAX, u128::cmp), v.len());
+    }
+
+    #[test]
+    fn test_probe_counts() -> Result<(), Box<dyn std::error::Error>> {
+        // Probes of binary, exponential and interpolation search for target; keys are distinct,
+        // so all of them find the same index.
+        let count = |v: &[u128], target: u128| -> (usize, usize, usize) {
+            let mut probes = (0, 0, 0);
+            let index = partition_point(v, |&x| {
+                probes.0 += 1;
+                x < target
+            });
+            let res = exponential_search_by(|i| {
+                probes.1 += 1;
+                v.get(i).map(|x| x.cmp(&target))
+            });
+            assert_eq!(res.unwrap_or_else(|i| i), index);
+            let res = interpolation_search_by_key(v, target, |&x| {
+                probes.2 += 1;
+                x
+            });
+            assert_eq!(res.unwrap_or_else(|i| i), index);
+            probes
+        };
+        let count_all = |v: &[u128]| {
+            v.iter().map(|&target| count(v, target)).fold((0, 0, 0), |sum, probes| {
+                (sum.0 + probes.0, sum.1 + probes.1, sum.2 + probes.2)
+            })
+        };
+        print!("\n\n Probes\n");
+        println!(
+            "{:>6} {:>6} {:>12} {:>12} {:>14}",
+            "n", "target", "binary", "exponential", "interpolation"
+        );
+        for case in gen_cases() {
+            let probes = count(&case.0, case.1);
+            println!(
+                "{:>6} {:>6} {:>12} {:>12} {:>14}",
+                case.0.len(),
+                case.1,
+                probes.0,
+                probes.1,
+                probes.2
+            );
+        }
+
+        // Uniform keys: interpolation guesses the position at once.
+        let v = gen_v(4096);
+        let (binary, exponential, interpolation) = count_all(&v);
+        if interpolation * 2 > binary || exponential > binary * 2 {
+            return Err(format!("uniform: {} {} {}", binary, exponential, interpolation).into());
+        }
+        // Targets near the beginning: galloping doesn't look at the rest.
+        for target in 1..16 {
+            let (binary, exponential, _) = count(&v, target);
+            if exponential >= binary {
+                return Err(format!("near {}: {} {}", target, binary, exponential).into());
+            }
+        }
+        // Exponentially growing keys: interpolation moves by one element per probe.
+        let skewed: Vec<u128> = (0..128).map(|i| 1 << i).collect();
+        let (binary, _, interpolation) = count_all(&skewed);
+        if interpolation < binary * 4 {
+            return Err(format!("skewed: {} {}", binary, interpolation).into());
+        }
+        Ok(())
+    }
+
+    pub(crate) fn gen_cases() -> Vec<(Vec<u128>, u128, Result<usize, usize>)> {
         vec![
-            (vec![3, 6, 9, 12, 15, 19, 22, 77], 6, Some(1)),
-            (vec![3, 6, 9, 12, 15, 19, 22, 77], 19, Some(5)),
-            (vec![3, 6, 9, 12, 15, 19, 22, 77], 77, Some(7)),
-            (vec![3, 6, 9, 12, 15, 19, 22, 77], 3, Some(0)),
-            (gen_v(100), 54, Some(53)),
-            (gen_v(256), 54, Some(53)),
-            (gen_v(256), 555, None),
-            (gen_v(3), 0, None),
-            (gen_v(256), 0, None),
-            (gen_v(256), 257, None),
-            (gen_v(256), 255, Some(254)),
+            (vec![], 5, Err(0)),
+            (vec![3, 6, 9, 12, 15, 19, 22, 77], 10, Err(3)),
+            (vec![3, 6, 9, 12, 15, 19, 22, 77], 6, Ok(1)),
+            (vec![3, 6, 9, 12, 15, 19, 22, 77], 19, Ok(5)),
+            (vec![3, 6, 9, 12, 15, 19, 22, 77], 77, Ok(7)),
+            (vec![3, 6, 9, 12, 15, 19, 22, 77], 3, Ok(0)),
+            (gen_v(100), 54, Ok(53)),
+            (gen_v(256), 54, Ok(53)),
+            (gen_v(256), 555, Err(256)),
+            (gen_v(3), 0, Err(0)),
+            (gen_v(256), 0, Err(0)),
+            (gen_v(256), 257, Err(256)),
+            (gen_v(256), 255, Ok(254)),
         ]
     }
 
-    fn gen_v(size: usize) -> Vec<u128> {
+    pub(crate) fn gen_v(size: usize) -> Vec<u128> {
         let mut v: Vec<u128> = Vec::with_capacity(size);
         for i in 0..size {
             v.push(i as u128 + 1)
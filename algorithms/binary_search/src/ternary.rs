@@ -0,0 +1,101 @@
+use std::ops::Range;
+
+// Index in range where unimodal f is the biggest, None if range is empty.
+// f has to strictly increase up to the maximum and strictly decrease after it:
+// on plateaus the side with the maximum can't be told.
+pub fn ternary_search_max<K, F>(range: Range<usize>, mut f: F) -> Option<usize>
+where
+    K: PartialOrd,
+    F: FnMut(usize) -> K,
+{
+    if range.is_empty() {
+        return None;
+    }
+    // Maximum is in [low, high].
+    let (mut low, mut high) = (range.start, range.end - 1);
+    while high - low > 2 {
+        let third = (high - low) / 3;
+        let (m1, m2) = (low + third, high - third);
+        if f(m1) < f(m2) {
+            low = m1 + 1
+        } else {
+            high = m2 - 1
+        }
+    }
+    let mut best = low;
+    let mut best_value = f(low);
+    for i in low + 1..=high {
+        let value = f(i);
+        if value > best_value {
+            best = i;
+            best_value = value
+        }
+    }
+    Some(best)
+}
+
+// x in [lo, hi] where unimodal f is the biggest, found within eps.
+pub fn ternary_search_float<F>(mut lo: f64, mut hi: f64, eps: f64, mut f: F) -> f64
+where
+    F: FnMut(f64) -> f64,
+{
+    while hi - lo > eps {
+        let third = (hi - lo) / 3.0;
+        let (m1, m2) = (lo + third, hi - third);
+        // eps is smaller than precision, so range can't get narrower.
+        if m1 <= lo || m2 >= hi {
+            break;
+        }
+        if f(m1) < f(m2) {
+            lo = m1
+        } else {
+            hi = m2
+        }
+    }
+    lo + (hi - lo) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ternary::*;
+    use crate::tests::gen_v;
+
+    #[test]
+    fn test_ternary_search_max() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(ternary_search_max(0..0, |i| i), None);
+        for len in 1..=64 {
+            // Increasing, decreasing, and increasing up to every peak and decreasing after it.
+            let up = gen_v(len);
+            let down: Vec<u128> = up.iter().rev().copied().collect();
+            assert_eq!(ternary_search_max(0..len, |i| up[i]), Some(len - 1));
+            assert_eq!(ternary_search_max(0..len, |i| down[i]), Some(0));
+            for peak in 0..len {
+                let top = peak as i128 + 1;
+                let v: Vec<i128> = up
+                    .iter()
+                    .map(|&x| x as i128)
+                    .map(|x| if x <= top { x } else { 2 * top - x })
+                    .collect();
+                let res = ternary_search_max(0..len, |i| v[i]);
+                if res != Some(peak) {
+                    return Err(format!("{:?} -> {:?}", v, res).into());
+                }
+            }
+        }
+        // Subrange and function without slice.
+        assert_eq!(ternary_search_max(10..100, |i| -((i as i64 - 42).pow(2))), Some(42));
+        assert_eq!(ternary_search_max(50..100, |i| -((i as i64 - 42).pow(2))), Some(50));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ternary_search_float() {
+        let x = ternary_search_float(-10.0, 10.0, 1e-9, |x| -(x - 1.5) * (x - 1.5) + 4.0);
+        assert!((x - 1.5).abs() <= 1e-6);
+        let x = ternary_search_float(0.0, std::f64::consts::PI, 1e-9, f64::sin);
+        assert!((x - std::f64::consts::FRAC_PI_2).abs() <= 1e-6);
+        // eps below precision stops instead of looping forever.
+        let x = ternary_search_float(0.0, 1.0, 0.0, |x| -x);
+        assert!(x <= 1e-9);
+    }
+}
@@ -0,0 +1,424 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use crate::{ActResult, Link, NodeName, Weight};
+
+// Undirected adjacency: node name -> (neighbor name -> cheapest weight between them).
+type Adjacency = BTreeMap<NodeName, BTreeMap<NodeName, Weight>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    fn opposite(self) -> Self {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+}
+
+// Collect every node reachable from root and treat edges as undirected.
+// If there are several edges between two nodes the cheapest one wins.
+fn undirected<T>(root: Link<T>) -> Adjacency
+where
+    T: Clone,
+{
+    let adjacency: RefCell<Adjacency> = RefCell::new(BTreeMap::new());
+    root.borrow().traverse_breadth_first(
+        &|edge| -> ActResult {
+            let node = edge.0.borrow();
+            let mut adjacency = adjacency.borrow_mut();
+            adjacency.entry(node.name.clone()).or_default();
+            for (child, weight) in &node.edges {
+                let child_name = child.borrow().name.clone();
+                for (from, to) in [(&node.name, &child_name), (&child_name, &node.name)] {
+                    let neighbors = adjacency.entry(from.clone()).or_default();
+                    let cheapest = neighbors.entry(to.clone()).or_insert(*weight);
+                    if *weight < *cheapest {
+                        *cheapest = *weight;
+                    }
+                }
+            }
+            ActResult::Ok
+        },
+        &mut HashSet::new(),
+    );
+    adjacency.into_inner()
+}
+
+// Root is always on the left side.
+fn color(adjacency: &Adjacency, root: &NodeName) -> Option<HashMap<NodeName, Side>> {
+    let mut sides: HashMap<NodeName, Side> = HashMap::with_capacity(adjacency.len());
+    // Graph can consist of several components, so start breadth first search from every uncolored node.
+    for start in std::iter::once(root).chain(adjacency.keys()) {
+        if sides.contains_key(start) {
+            continue;
+        }
+        sides.insert(start.clone(), Side::Left);
+        let mut queue: VecDeque<&NodeName> = VecDeque::from([start]);
+        while let Some(name) = queue.pop_front() {
+            let side = sides[name];
+            for neighbor in adjacency[name].keys() {
+                match sides.get(neighbor) {
+                    // Two neighbors with the same color -> odd cycle.
+                    Some(neighbor_side) if *neighbor_side == side => return None,
+                    Some(_) => (),
+                    None => {
+                        sides.insert(neighbor.clone(), side.opposite());
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+        }
+    }
+    Some(sides)
+}
+
+// Split nodes into two sorted parts of the bipartite graph.
+fn split(sides: &HashMap<NodeName, Side>) -> (Vec<NodeName>, Vec<NodeName>) {
+    let mut left: Vec<NodeName> = Vec::new();
+    let mut right: Vec<NodeName> = Vec::new();
+    for (name, side) in sides {
+        match side {
+            Side::Left => left.push(name.clone()),
+            Side::Right => right.push(name.clone()),
+        }
+    }
+    left.sort();
+    right.sort();
+    (left, right)
+}
+
+// Two-coloring of all nodes reachable from root; edges are treated as undirected.
+// Returns None if graph has an odd cycle and so it is not bipartite.
+pub fn two_coloring<T>(root: Link<T>) -> Option<HashMap<NodeName, Side>>
+where
+    T: Clone,
+{
+    let name = root.borrow().name.clone();
+    color(&undirected(root), &name)
+}
+
+pub fn is_bipartite<T>(root: Link<T>) -> bool
+where
+    T: Clone,
+{
+    two_coloring(root).is_some()
+}
+
+// Maximum cardinality matching for bipartite graph.
+// Returns pairs from left side to right side or None if graph is not bipartite.
+pub fn hopcroft_karp<T>(root: Link<T>) -> Option<HashMap<NodeName, NodeName>>
+where
+    T: Clone,
+{
+    let name = root.borrow().name.clone();
+    let adjacency = undirected(root);
+    let (left, right) = split(&color(&adjacency, &name)?);
+    let right_index: HashMap<&NodeName, usize> =
+        right.iter().enumerate().map(|(i, name)| (name, i)).collect();
+    let neighbors: Vec<Vec<usize>> = left
+        .iter()
+        .map(|name| adjacency[name].keys().map(|neighbor| right_index[neighbor]).collect())
+        .collect();
+
+    let mut pair_left: Vec<Option<usize>> = vec![None; left.len()];
+    let mut pair_right: Vec<Option<usize>> = vec![None; right.len()];
+    let mut dist: Vec<usize> = vec![0; left.len()];
+    while hopcroft_karp_bfs(&neighbors, &pair_left, &pair_right, &mut dist) {
+        for u in 0..left.len() {
+            if pair_left[u].is_none() {
+                hopcroft_karp_dfs(u, &neighbors, &mut pair_left, &mut pair_right, &mut dist);
+            }
+        }
+    }
+
+    Some(
+        pair_left
+            .iter()
+            .enumerate()
+            .filter_map(|(u, v)| v.map(|v| (left[u].clone(), right[v].clone())))
+            .collect(),
+    )
+}
+
+// Build layers from free left nodes. Returns true if there is at least one augmenting path.
+fn hopcroft_karp_bfs(
+    neighbors: &[Vec<usize>],
+    pair_left: &[Option<usize>],
+    pair_right: &[Option<usize>],
+    dist: &mut [usize],
+) -> bool {
+    let mut queue: VecDeque<usize> = VecDeque::new();
+    for u in 0..neighbors.len() {
+        if pair_left[u].is_none() {
+            dist[u] = 0;
+            queue.push_back(u);
+        } else {
+            dist[u] = usize::MAX;
+        }
+    }
+    let mut found = false;
+    while let Some(u) = queue.pop_front() {
+        for &v in &neighbors[u] {
+            match pair_right[v] {
+                // Free right node ends augmenting path.
+                None => found = true,
+                Some(next) if dist[next] == usize::MAX => {
+                    dist[next] = dist[u] + 1;
+                    queue.push_back(next);
+                }
+                Some(_) => (),
+            }
+        }
+    }
+    found
+}
+
+// Walk along layers and flip matched edges of found augmenting path.
+fn hopcroft_karp_dfs(
+    u: usize,
+    neighbors: &[Vec<usize>],
+    pair_left: &mut [Option<usize>],
+    pair_right: &mut [Option<usize>],
+    dist: &mut [usize],
+) -> bool {
+    for &v in &neighbors[u] {
+        let augmented = match pair_right[v] {
+            None => true,
+            Some(next) => {
+                dist[next] == dist[u] + 1
+                    && hopcroft_karp_dfs(next, neighbors, pair_left, pair_right, dist)
+            }
+        };
+        if augmented {
+            pair_left[u] = Some(v);
+            pair_right[v] = Some(u);
+            return true;
+        }
+    }
+    // Remove node from layers as there is no augmenting path through it.
+    dist[u] = usize::MAX;
+    false
+}
+
+// Minimum cost assignment for bipartite graph using edge weights as costs.
+// Matches as many nodes as possible and among such matchings picks the cheapest one.
+// Returns total cost with pairs from left side to right side or None if graph is not bipartite.
+pub fn hungarian<T>(root: Link<T>) -> Option<(Weight, HashMap<NodeName, NodeName>)>
+where
+    T: Clone,
+{
+    let name = root.borrow().name.clone();
+    let adjacency = undirected(root);
+    let (mut rows, mut columns) = split(&color(&adjacency, &name)?);
+    // Algorithm requires rows count to be less or equal to columns count.
+    let transposed = rows.len() > columns.len();
+    if transposed {
+        std::mem::swap(&mut rows, &mut columns);
+    }
+
+    // Missing edges cost more than any full set of real edges,
+    // so they are used only when there is no other way to assign a row.
+    let missing: Weight = adjacency
+        .values()
+        .flat_map(|neighbors| neighbors.values())
+        .map(|weight| weight.abs())
+        .sum::<Weight>()
+        + 1;
+    let cost = |row: usize, column: usize| -> Weight {
+        adjacency[&rows[row]].get(&columns[column]).copied().unwrap_or(missing)
+    };
+
+    let assignment = hungarian_assign(rows.len(), columns.len(), cost);
+
+    let mut total: Weight = 0;
+    let mut pairs: HashMap<NodeName, NodeName> = HashMap::with_capacity(rows.len());
+    for (row, column) in assignment.into_iter().enumerate() {
+        let Some(weight) = adjacency[&rows[row]].get(&columns[column]) else {
+            continue;
+        };
+        total += weight;
+        let (row, column) = (rows[row].clone(), columns[column].clone());
+        if transposed {
+            pairs.insert(column, row);
+        } else {
+            pairs.insert(row, column);
+        }
+    }
+    Some((total, pairs))
+}
+
+// Classic O(n^2 * m) Hungarian algorithm with potentials, n <= m.
+// Returns assigned column for every row.
+fn hungarian_assign<F>(n: usize, m: usize, cost: F) -> Vec<usize>
+where
+    F: Fn(usize, usize) -> Weight,
+{
+    // Arrays are 1-indexed, zero column is a fictive one.
+    let mut u: Vec<Weight> = vec![0; n + 1];
+    let mut v: Vec<Weight> = vec![0; m + 1];
+    // Row assigned to column.
+    let mut p: Vec<usize> = vec![0; m + 1];
+    let mut way: Vec<usize> = vec![0; m + 1];
+    for i in 1..=n {
+        p[0] = i;
+        let mut j0: usize = 0;
+        let mut min_v: Vec<Weight> = vec![Weight::MAX; m + 1];
+        let mut used: Vec<bool> = vec![false; m + 1];
+        loop {
+            used[j0] = true;
+            let i0 = p[j0];
+            let mut delta = Weight::MAX;
+            let mut j1: usize = 0;
+            for j in 1..=m {
+                if used[j] {
+                    continue;
+                }
+                let cur = cost(i0 - 1, j - 1) - u[i0] - v[j];
+                if cur < min_v[j] {
+                    min_v[j] = cur;
+                    way[j] = j0;
+                }
+                if min_v[j] < delta {
+                    delta = min_v[j];
+                    j1 = j;
+                }
+            }
+            for j in 0..=m {
+                if used[j] {
+                    u[p[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min_v[j] -= delta;
+                }
+            }
+            j0 = j1;
+            if p[j0] == 0 {
+                break;
+            }
+        }
+        // Flip assignments along found path.
+        loop {
+            let j1 = way[j0];
+            p[j0] = p[j1];
+            j0 = j1;
+            if j0 == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut assignment: Vec<usize> = vec![0; n];
+    for j in 1..=m {
+        if p[j] != 0 {
+            assignment[p[j] - 1] = j - 1;
+        }
+    }
+    assignment
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    use crate::bipartite::*;
+    use crate::Node;
+
+    #[test]
+    fn test_two_coloring() {
+        let root = gen_graph_jobs();
+        let sides = two_coloring(root.clone()).unwrap();
+        for worker in ["W1", "W2", "W3"] {
+            assert_eq!(sides[worker], Side::Left);
+        }
+        for job in ["J1", "J2", "J3"] {
+            assert_eq!(sides[job], Side::Right);
+        }
+        assert!(is_bipartite(root));
+
+        // Triangle is the smallest odd cycle.
+        let a = Node::new(String::from("A"), 0);
+        let b = Node::new(String::from("B"), 0);
+        let c = Node::new(String::from("C"), 0);
+        a.borrow_mut().add_edge(Rc::clone(&b), 1);
+        b.borrow_mut().add_edge(Rc::clone(&c), 1);
+        c.borrow_mut().add_edge(Rc::clone(&a), 1);
+        assert_eq!(two_coloring(a.clone()), None);
+        assert_eq!(hopcroft_karp(a.clone()), None);
+        assert_eq!(hungarian(a), None);
+    }
+
+    #[test]
+    fn test_hopcroft_karp() {
+        let root = gen_graph_jobs();
+        let matching = hopcroft_karp(root).unwrap();
+        // W1 can be matched only with J2 in a perfect matching, others have two options.
+        assert_eq!(matching.len(), 3);
+        assert_eq!(matching["W1"], "J2");
+        assert_ne!(matching["W2"], matching["W3"]);
+
+        // Only one job for two workers.
+        let w1 = Node::new(String::from("W1"), 0);
+        let w2 = Node::new(String::from("W2"), 0);
+        let j1 = Node::new(String::from("J1"), 0);
+        link(&w1, &j1, 1);
+        link(&w2, &j1, 1);
+        assert_eq!(hopcroft_karp(w1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_hungarian() {
+        let root = gen_graph_jobs();
+        let (cost, assignment) = hungarian(root).unwrap();
+        assert_eq!(cost, 9);
+        assert_eq!(
+            assignment,
+            HashMap::from([
+                (String::from("W1"), String::from("J2")),
+                (String::from("W2"), String::from("J1")),
+                (String::from("W3"), String::from("J3")),
+            ])
+        );
+
+        // More jobs than workers; the cheapest job has to be chosen.
+        let w1 = Node::new(String::from("W1"), 0);
+        let j1 = Node::new(String::from("J1"), 0);
+        let j2 = Node::new(String::from("J2"), 0);
+        link(&w1, &j1, 5);
+        link(&w1, &j2, -2);
+        let (cost, assignment) = hungarian(w1).unwrap();
+        assert_eq!(cost, -2);
+        assert_eq!(assignment, HashMap::from([(String::from("W1"), String::from("J2"))]));
+    }
+
+    fn link(a: &Link<u8>, b: &Link<u8>, weight: Weight) {
+        a.borrow_mut().add_edge(Rc::clone(b), weight);
+        b.borrow_mut().add_edge(Rc::clone(a), weight);
+    }
+
+    // Workers and jobs they can do with cost of each job.
+    // The cheapest full assignment is W1 -> J2, W2 -> J1, W3 -> J3 with cost 2 + 3 + 4 = 9.
+    fn gen_graph_jobs() -> Link<u8> {
+        let w1 = Node::new(String::from("W1"), 0);
+        let w2 = Node::new(String::from("W2"), 0);
+        let w3 = Node::new(String::from("W3"), 0);
+        let j1 = Node::new(String::from("J1"), 1);
+        let j2 = Node::new(String::from("J2"), 1);
+        let j3 = Node::new(String::from("J3"), 1);
+
+        link(&w1, &j1, 1);
+        link(&w1, &j2, 2);
+        link(&w2, &j1, 3);
+        link(&w2, &j3, 8);
+        link(&w3, &j1, 2);
+        link(&w3, &j3, 4);
+
+        w1
+    }
+}
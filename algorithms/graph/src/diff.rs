@@ -0,0 +1,388 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
+
+use crate::{ActResult, Link, Node, NodeName, Weight};
+
+// Node data with edges to neighbors by name.
+// Several edges between two nodes are kept as sorted weights of all of them.
+type Snapshot<T> = BTreeMap<NodeName, (T, BTreeMap<NodeName, Vec<Weight>>)>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffEdge {
+    pub from: NodeName,
+    pub to: NodeName,
+    pub weight: Weight,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightChange {
+    pub from: NodeName,
+    pub to: NodeName,
+    pub old: Weight,
+    pub new: Weight,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataChange<T> {
+    pub name: NodeName,
+    pub old: T,
+    pub new: T,
+}
+
+// Difference between two graphs where nodes are matched by name.
+// All lists are sorted by node names.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphDiff<T> {
+    pub added_nodes: Vec<NodeName>,
+    pub removed_nodes: Vec<NodeName>,
+    pub added_edges: Vec<DiffEdge>,
+    pub removed_edges: Vec<DiffEdge>,
+    pub changed_weights: Vec<WeightChange>,
+    pub changed_data: Vec<DataChange<T>>,
+}
+
+impl<T> GraphDiff<T> {
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.changed_weights.is_empty()
+            && self.changed_data.is_empty()
+    }
+}
+
+// What to do when both graphs have the same node with different data
+// or the same edge with different weight.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MergePolicy {
+    KeepLeft,
+    KeepRight,
+    Fail,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum MergeConflict {
+    Data(NodeName),
+    Weight(NodeName, NodeName),
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeConflict::Data(name) => write!(f, "node {} has different data", name),
+            MergeConflict::Weight(from, to) => {
+                write!(f, "edge {} -> {} has different weight", from, to)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MergeConflict {}
+
+fn snapshot<T>(root: Link<T>) -> Snapshot<T>
+where
+    T: Clone,
+{
+    let nodes: RefCell<Snapshot<T>> = RefCell::new(BTreeMap::new());
+    root.borrow().traverse_breadth_first(
+        &|edge| -> ActResult {
+            let node = edge.0.borrow();
+            let mut edges: BTreeMap<NodeName, Vec<Weight>> = BTreeMap::new();
+            for (child, weight) in &node.edges {
+                edges.entry(child.borrow().name.clone()).or_default().push(*weight);
+            }
+            edges.values_mut().for_each(|weights| weights.sort());
+            nodes.borrow_mut().insert(node.name.clone(), (node.data.clone(), edges));
+            ActResult::Ok
+        },
+        &mut HashSet::new(),
+    );
+    nodes.into_inner()
+}
+
+// Compare graphs reachable from a and b roots.
+// Edges of added and removed nodes are reported as added and removed edges too.
+pub fn diff<T>(a: Link<T>, b: Link<T>) -> GraphDiff<T>
+where
+    T: Clone + PartialEq,
+{
+    let a = snapshot(a);
+    let b = snapshot(b);
+    let mut diff = GraphDiff {
+        added_nodes: Vec::new(),
+        removed_nodes: Vec::new(),
+        added_edges: Vec::new(),
+        removed_edges: Vec::new(),
+        changed_weights: Vec::new(),
+        changed_data: Vec::new(),
+    };
+    let edge = |from: &NodeName, to: &NodeName, weight: &Weight| DiffEdge {
+        from: from.clone(),
+        to: to.clone(),
+        weight: *weight,
+    };
+
+    for (name, (data, edges)) in &a {
+        let Some((new_data, new_edges)) = b.get(name) else {
+            diff.removed_nodes.push(name.clone());
+            diff.removed_edges.extend(all_edges(name, edges));
+            continue;
+        };
+        if data != new_data {
+            diff.changed_data.push(DataChange {
+                name: name.clone(),
+                old: data.clone(),
+                new: new_data.clone(),
+            });
+        }
+        let targets: BTreeSet<&NodeName> = edges.keys().chain(new_edges.keys()).collect();
+        for to in targets {
+            let old = edges.get(to).map_or(&[][..], Vec::as_slice);
+            let new = new_edges.get(to).map_or(&[][..], Vec::as_slice);
+            let (removed, added) = unmatched(old, new);
+            // Unmatched parallel edges are paired as changed weights, the rest are removed or added.
+            let changed = removed.len().min(added.len());
+            for (old, new) in removed.iter().zip(&added) {
+                diff.changed_weights.push(WeightChange {
+                    from: name.clone(),
+                    to: to.clone(),
+                    old: *old,
+                    new: *new,
+                });
+            }
+            diff.removed_edges.extend(removed[changed..].iter().map(|w| edge(name, to, w)));
+            diff.added_edges.extend(added[changed..].iter().map(|w| edge(name, to, w)));
+        }
+    }
+    for (name, (_, edges)) in &b {
+        if !a.contains_key(name) {
+            diff.added_nodes.push(name.clone());
+            diff.added_edges.extend(all_edges(name, edges));
+        }
+    }
+    diff.added_edges.sort_by(|x, y| (&x.from, &x.to, x.weight).cmp(&(&y.from, &y.to, y.weight)));
+
+    diff
+}
+
+fn all_edges<'a>(
+    from: &'a NodeName,
+    edges: &'a BTreeMap<NodeName, Vec<Weight>>,
+) -> impl Iterator<Item = DiffEdge> + 'a {
+    edges.iter().flat_map(move |(to, weights)| {
+        weights.iter().map(move |weight| DiffEdge {
+            from: from.clone(),
+            to: to.clone(),
+            weight: *weight,
+        })
+    })
+}
+
+// Weights of old and new sorted lists which have no equal pair in the other list.
+fn unmatched(old: &[Weight], new: &[Weight]) -> (Vec<Weight>, Vec<Weight>) {
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if j == new.len() || (i < old.len() && old[i] < new[j]) {
+            removed.push(old[i]);
+            i += 1;
+        } else if i == old.len() || new[j] < old[i] {
+            added.push(new[j]);
+            j += 1;
+        } else {
+            i += 1;
+            j += 1;
+        }
+    }
+    (removed, added)
+}
+
+// Union of graphs reachable from a and b roots where nodes are matched by name.
+// Returns root of merged graph with the same name as a root.
+// Nodes that exist only in b are reachable from it only through merged edges.
+pub fn merge<T>(a: Link<T>, b: Link<T>, policy: MergePolicy) -> Result<Link<T>, MergeConflict>
+where
+    T: Clone + PartialEq,
+{
+    let root_name = a.borrow().name.clone();
+    let mut merged = snapshot(a);
+    for (name, (data, edges)) in snapshot(b) {
+        let Some((merged_data, merged_edges)) = merged.get_mut(&name) else {
+            merged.insert(name, (data, edges));
+            continue;
+        };
+        if *merged_data != data {
+            match policy {
+                MergePolicy::KeepLeft => (),
+                MergePolicy::KeepRight => *merged_data = data,
+                MergePolicy::Fail => return Err(MergeConflict::Data(name)),
+            }
+        }
+        // Parallel edges between the same nodes conflict if their weights differ as a whole.
+        for (to, weights) in edges {
+            let Some(merged_weights) = merged_edges.get_mut(&to) else {
+                merged_edges.insert(to, weights);
+                continue;
+            };
+            if *merged_weights != weights {
+                match policy {
+                    MergePolicy::KeepLeft => (),
+                    MergePolicy::KeepRight => *merged_weights = weights,
+                    MergePolicy::Fail => return Err(MergeConflict::Weight(name, to)),
+                }
+            }
+        }
+    }
+
+    let links: HashMap<&NodeName, Link<T>> = merged
+        .iter()
+        .map(|(name, (data, _))| (name, Node::new(name.clone(), data.clone())))
+        .collect();
+    for (name, (_, edges)) in &merged {
+        let mut link = links[name].borrow_mut();
+        for (to, weights) in edges {
+            for weight in weights {
+                link.add_edge(Rc::clone(&links[to]), *weight);
+            }
+        }
+    }
+    Ok(Rc::clone(&links[&root_name]))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::diff::*;
+    use crate::tests::gen_graph;
+    use crate::{dijkstra, Node};
+
+    #[test]
+    fn test_diff() {
+        assert!(diff(gen_graph(), gen_graph()).is_empty());
+
+        let diff = diff(gen_graph(), gen_graph_changed());
+        assert_eq!(diff.added_nodes, vec![String::from("H")]);
+        assert_eq!(diff.removed_nodes, vec![String::from("D")]);
+        assert_eq!(diff.added_edges, vec![gen_edge("A", "H", 2), gen_edge("H", "G", 1)]);
+        assert_eq!(diff.removed_edges, vec![gen_edge("B", "D", 2), gen_edge("C", "D", 7)]);
+        assert_eq!(
+            diff.changed_weights,
+            vec![WeightChange {
+                from: String::from("R"),
+                to: String::from("B"),
+                old: 9,
+                new: 3,
+            }]
+        );
+        assert_eq!(
+            diff.changed_data,
+            vec![DataChange {
+                name: String::from("E"),
+                old: 5,
+                new: 50,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        assert_eq!(
+            merge(gen_graph(), gen_graph_changed(), MergePolicy::Fail).err(),
+            Some(MergeConflict::Data(String::from("E")))
+        );
+
+        let merged = merge(gen_graph(), gen_graph_changed(), MergePolicy::KeepLeft).unwrap();
+        let (costs, _) = dijkstra(merged.clone());
+        // Both D and H are in the merged graph, old weight of R -> B is kept.
+        assert_eq!(costs["D"], 11);
+        assert_eq!(costs["H"], 3);
+        assert_eq!(costs["B"], 9);
+
+        let merged = merge(gen_graph(), gen_graph_changed(), MergePolicy::KeepRight).unwrap();
+        let (costs, _) = dijkstra(merged.clone());
+        assert_eq!(costs["B"], 3);
+        assert_eq!(costs["E"], 8);
+        let changed = diff(gen_graph_changed(), merged);
+        assert_eq!(changed.added_nodes, vec![String::from("D")]);
+        assert!(changed.changed_data.is_empty());
+        assert!(changed.changed_weights.is_empty());
+    }
+
+    #[test]
+    fn test_parallel_edges() {
+        let gen = |weights: &[Weight]| -> Link<u8> {
+            let r = Node::new(String::from("R"), 0);
+            let a = Node::new(String::from("A"), 1);
+            for weight in weights {
+                r.borrow_mut().add_edge(Rc::clone(&a), *weight);
+            }
+            r
+        };
+        let count = |root: Link<u8>| root.borrow().edges.len();
+
+        assert!(diff(gen(&[4, 2, 4]), gen(&[2, 4, 4])).is_empty());
+        let changed = diff(gen(&[4, 2, 4]), gen(&[2, 4]));
+        assert_eq!(changed.removed_edges, vec![gen_edge("R", "A", 4)]);
+        assert!(changed.added_edges.is_empty() && changed.changed_weights.is_empty());
+        let changed = diff(gen(&[2, 4]), gen(&[2, 5, 5]));
+        assert_eq!(changed.added_edges, vec![gen_edge("R", "A", 5)]);
+        assert_eq!(
+            changed.changed_weights,
+            vec![WeightChange {
+                from: String::from("R"),
+                to: String::from("A"),
+                old: 4,
+                new: 5,
+            }]
+        );
+
+        // Duplicated edge survives merge round trip.
+        let merged = merge(gen(&[3, 3]), gen(&[3, 3]), MergePolicy::Fail).unwrap();
+        assert_eq!(count(merged.clone()), 2);
+        assert!(diff(gen(&[3, 3]), merged).is_empty());
+        let merged = merge(gen(&[3, 3]), gen(&[1]), MergePolicy::KeepLeft).unwrap();
+        assert_eq!(count(merged), 2);
+        let merged = merge(gen(&[3]), gen(&[1, 1]), MergePolicy::KeepRight).unwrap();
+        assert!(diff(gen(&[1, 1]), merged).is_empty());
+        assert_eq!(
+            merge(gen(&[3, 3]), gen(&[3]), MergePolicy::Fail).err(),
+            Some(MergeConflict::Weight(String::from("R"), String::from("A")))
+        );
+    }
+
+    fn gen_edge(from: &str, to: &str, weight: Weight) -> DiffEdge {
+        DiffEdge {
+            from: String::from(from),
+            to: String::from(to),
+            weight,
+        }
+    }
+
+    // Same as gen_graph but without D, with new H, changed E data and changed R -> B weight.
+    fn gen_graph_changed() -> Link<u8> {
+        let r = Node::new(String::from("R"), 0);
+        let a = Node::new(String::from("A"), 1);
+        let b = Node::new(String::from("B"), 2);
+        let c = Node::new(String::from("C"), 3);
+        let e = Node::new(String::from("E"), 50);
+        let f = Node::new(String::from("F"), 6);
+        let g = Node::new(String::from("G"), 7);
+        let h = Node::new(String::from("H"), 8);
+
+        r.borrow_mut().add_edge(Rc::clone(&a), 1).add_edge(Rc::clone(&b), 3);
+        a.borrow_mut()
+            .add_edge(Rc::clone(&c), 6)
+            .add_edge(Rc::clone(&g), 3)
+            .add_edge(Rc::clone(&h), 2);
+        b.borrow_mut().add_edge(Rc::clone(&e), 5);
+        e.borrow_mut().add_edge(Rc::clone(&f), 8);
+        f.borrow_mut().add_edge(Rc::clone(&a), 0).add_edge(Rc::clone(&g), 4);
+        h.borrow_mut().add_edge(Rc::clone(&g), 1);
+
+        r
+    }
+}
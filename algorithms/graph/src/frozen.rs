@@ -0,0 +1,209 @@
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::{ActResult, Link, NodeName, Weight};
+
+// Costs and parent names for every node of frozen graph.
+pub type FrozenPaths = (HashMap<NodeName, Weight>, HashMap<NodeName, Option<NodeName>>);
+// Node name, data and edges to neighbors by name.
+type Collected<T> = Vec<(NodeName, T, Vec<(NodeName, Weight)>)>;
+
+#[derive(Debug, PartialEq)]
+pub struct FrozenNode<T> {
+    pub name: NodeName,
+    pub data: T,
+    // Indexes of neighbors in graph nodes with edge weights.
+    pub edges: Vec<(usize, Weight)>,
+}
+
+// Immutable copy of the graph without Rc links, so it is Send + Sync when T is.
+// Build it once from mutable form and share between threads by reference or in Arc.
+#[derive(Debug, PartialEq)]
+pub struct FrozenGraph<T> {
+    nodes: Vec<FrozenNode<T>>,
+    index: HashMap<NodeName, usize>,
+}
+
+impl<T> FrozenGraph<T>
+where
+    T: Clone,
+{
+    // Freeze all nodes reachable from root.
+    pub fn new(root: Link<T>) -> Self {
+        // Collect nodes in breadth first order, so root always has zero index.
+        let collected: RefCell<Collected<T>> = RefCell::new(Vec::new());
+        root.borrow().traverse_breadth_first(
+            &|edge| -> ActResult {
+                let node = edge.0.borrow();
+                let edges = node.edges.iter().map(|e| (e.0.borrow().name.clone(), e.1)).collect();
+                collected.borrow_mut().push((node.name.clone(), node.data.clone(), edges));
+                ActResult::Ok
+            },
+            &mut HashSet::new(),
+        );
+        let collected = collected.into_inner();
+
+        let index: HashMap<NodeName, usize> =
+            collected.iter().enumerate().map(|(i, node)| (node.0.clone(), i)).collect();
+        let nodes: Vec<FrozenNode<T>> = collected
+            .into_iter()
+            .map(|(name, data, edges)| FrozenNode {
+                name,
+                data,
+                edges: edges.into_iter().map(|(name, weight)| (index[&name], weight)).collect(),
+            })
+            .collect();
+        Self { nodes, index }
+    }
+}
+
+impl<T> FrozenGraph<T> {
+    pub fn root(&self) -> &FrozenNode<T> {
+        &self.nodes[0]
+    }
+
+    pub fn get(&self, name: &str) -> Option<&FrozenNode<T>> {
+        self.index.get(name).map(|i| &self.nodes[*i])
+    }
+
+    pub fn nodes(&self) -> &[FrozenNode<T>] {
+        &self.nodes
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    // Same as dijkstra for mutable graph but from any node of the graph.
+    // Nodes that can't be reached from source keep Weight::MAX cost.
+    // Returns None if there is no source node in the graph.
+    pub fn dijkstra(&self, source: &str) -> Option<FrozenPaths> {
+        let source = *self.index.get(source)?;
+        let mut costs: Vec<Weight> = vec![Weight::MAX; self.nodes.len()];
+        let mut parents: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        let mut processed: Vec<bool> = vec![false; self.nodes.len()];
+
+        costs[source] = 0;
+        let mut queue: BinaryHeap<Reverse<(Weight, usize)>> =
+            BinaryHeap::from([Reverse((0, source))]);
+        while let Some(Reverse((cost, closest))) = queue.pop() {
+            if processed[closest] {
+                continue;
+            }
+            for (child, weight) in &self.nodes[closest].edges {
+                let new_cost = cost + weight;
+                if costs[*child] > new_cost {
+                    costs[*child] = new_cost;
+                    parents[*child] = Some(closest);
+                    queue.push(Reverse((new_cost, *child)));
+                }
+            }
+            processed[closest] = true;
+        }
+
+        let names = || self.nodes.iter().map(|node| node.name.clone());
+        Some((
+            names().zip(costs).collect(),
+            names()
+                .zip(parents.into_iter().map(|p| p.map(|p| self.nodes[p].name.clone())))
+                .collect(),
+        ))
+    }
+}
+
+// Run dijkstra from every node of the graph using pool of workers.
+// Each worker takes next source from shared counter until all sources are processed.
+pub fn all_sources_dijkstra<T>(
+    graph: &FrozenGraph<T>,
+    workers: usize,
+) -> HashMap<NodeName, FrozenPaths>
+where
+    T: Sync,
+{
+    let next: AtomicUsize = AtomicUsize::new(0);
+    let results: Mutex<HashMap<NodeName, FrozenPaths>> =
+        Mutex::new(HashMap::with_capacity(graph.len()));
+    thread::scope(|s| {
+        for _ in 0..workers.max(1) {
+            s.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(source) = graph.nodes.get(i) else {
+                    break;
+                };
+                // Source is always in the graph.
+                let paths = graph.dijkstra(&source.name).unwrap();
+                results.lock().unwrap().insert(source.name.clone(), paths);
+            });
+        }
+    });
+    results.into_inner().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::thread;
+
+    use crate::dijkstra;
+    use crate::frozen::*;
+    use crate::tests::gen_graph;
+
+    #[test]
+    fn test_frozen_dijkstra() {
+        let root = gen_graph();
+        let graph = FrozenGraph::new(root.clone());
+        assert_eq!(graph.len(), 8);
+        assert_eq!(graph.root().name, "R");
+        assert_eq!(graph.get("F").unwrap().data, 6);
+        assert_eq!(graph.dijkstra("Press F"), None);
+
+        let (costs, parents) = graph.dijkstra("R").unwrap();
+        let (expected_costs, expected_parents) = dijkstra(root);
+        assert_eq!(costs, expected_costs);
+        for (name, parent) in expected_parents {
+            assert_eq!(parents[&name], parent.map(|p| p.borrow().name.clone()));
+        }
+
+        // D doesn't have any edges so everything else is unreachable.
+        let (costs, _) = graph.dijkstra("D").unwrap();
+        assert_eq!(costs["D"], 0);
+        assert_eq!(costs["R"], Weight::MAX);
+    }
+
+    #[test]
+    fn test_all_sources_dijkstra() {
+        let graph = Arc::new(FrozenGraph::new(gen_graph()));
+        let all = all_sources_dijkstra(&graph, 3);
+        assert_eq!(all.len(), graph.len());
+        for node in graph.nodes() {
+            assert_eq!(all[&node.name], graph.dijkstra(&node.name).unwrap());
+        }
+
+        // The same graph can be queried from other threads directly.
+        let handles: Vec<thread::JoinHandle<FrozenPaths>> = ["A", "B", "E"]
+            .into_iter()
+            .map(|source| {
+                let graph = Arc::clone(&graph);
+                thread::spawn(move || graph.dijkstra(source).unwrap())
+            })
+            .collect();
+        let results: HashMap<&str, FrozenPaths> = ["A", "B", "E"]
+            .into_iter()
+            .zip(handles.into_iter().map(|h| h.join().unwrap()))
+            .collect();
+        assert_eq!(results["A"], all["A"]);
+        assert_eq!(results["B"].0["F"], 13);
+        // E -> F -> A -> G is cheaper than E -> F -> G.
+        assert_eq!(results["E"].0["G"], 11);
+        assert_eq!(results["E"].1["G"], Some(String::from("A")));
+    }
+}
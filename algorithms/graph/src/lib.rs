@@ -2,13 +2,34 @@ use std::cell::RefCell;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::rc::Rc;
 
-type NodeName = String;
-type Weight = i128;
-type Link<T> = Rc<RefCell<Node<T>>>;
-type Edge<T> = (Link<T>, Weight);
+mod bipartite;
+mod diff;
+mod frozen;
+#[cfg(feature = "serde")]
+mod serialization;
+mod view;
+
+pub use bipartite::{hopcroft_karp, hungarian, is_bipartite, two_coloring, Side};
+pub use diff::{
+    diff, merge, DataChange, DiffEdge, GraphDiff, MergeConflict, MergePolicy, WeightChange,
+};
+pub use frozen::{all_sources_dijkstra, FrozenGraph, FrozenNode, FrozenPaths};
+#[cfg(feature = "serde")]
+pub use serialization::{
+    serde_frozen_paths, serde_paths, SerdeEdge, SerdeGraph, SerdeGraphError, SerdeNode, SerdePath,
+    SerdePaths,
+};
+pub use view::GraphView;
+
+pub type NodeName = String;
+pub type Weight = i128;
+pub type Link<T> = Rc<RefCell<Node<T>>>;
+pub type Edge<T> = (Link<T>, Weight);
+// Costs and parents for every reachable node.
+pub type ShortestPaths<T> = (HashMap<NodeName, Weight>, HashMap<NodeName, Option<Link<T>>>);
 
 // Traverse act result.
-enum ActResult {
+pub enum ActResult {
     Ok,
     Stop,
 }
@@ -17,17 +38,17 @@ enum ActResult {
 //          to avoid useless traverse before searches.
 
 #[derive(Debug, PartialEq)]
-struct Node<T> {
-    name: NodeName,
-    data: T,
-    edges: Vec<Edge<T>>,
+pub struct Node<T> {
+    pub name: NodeName,
+    pub data: T,
+    pub edges: Vec<Edge<T>>,
 }
 
 impl<T> Node<T>
 where
     T: Clone,
 {
-    fn new(name: NodeName, data: T) -> Link<T> {
+    pub fn new(name: NodeName, data: T) -> Link<T> {
         Rc::new(RefCell::new(Node {
             name,
             data,
@@ -35,12 +56,12 @@ where
         }))
     }
 
-    fn add_edge(&mut self, node: Link<T>, weight: Weight) -> &mut Self {
+    pub fn add_edge(&mut self, node: Link<T>, weight: Weight) -> &mut Self {
         self.edges.push((node, weight));
         self
     }
 
-    fn traverse_depth_first<F>(&self, act: &F, seen: &mut HashSet<NodeName>)
+    pub fn traverse_depth_first<F>(&self, act: &F, seen: &mut HashSet<NodeName>)
     where
         F: Fn(&Node<T>),
     {
@@ -54,7 +75,7 @@ where
         }
     }
 
-    fn traverse_breadth_first<F>(&self, act: &F, seen: &mut HashSet<NodeName>)
+    pub fn traverse_breadth_first<F>(&self, act: &F, seen: &mut HashSet<NodeName>)
     where
         F: Fn(&Edge<T>) -> ActResult,
     {
@@ -89,12 +110,19 @@ where
     }
 }
 
-fn breadth_first_search<T>(root: Link<T>, target: &str) -> Option<T>
+pub fn breadth_first_search<T>(root: Link<T>, target: &str) -> Option<T>
+where
+    T: Clone + Eq,
+{
+    breadth_first_search_view(&GraphView::new(root), target)
+}
+
+pub fn breadth_first_search_view<T>(view: &GraphView<T>, target: &str) -> Option<T>
 where
     T: Clone + Eq,
 {
     let found: RefCell<Option<T>> = RefCell::new(None);
-    root.borrow().traverse_breadth_first(
+    view.traverse_breadth_first(
         &|edge| -> ActResult {
             if edge.0.borrow().name == target {
                 *found.borrow_mut() = Some(edge.0.borrow().data.clone());
@@ -107,7 +135,7 @@ where
     found.take()
 }
 
-fn print_node<T>(node: &Node<T>) {
+pub fn print_node<T>(node: &Node<T>) {
     println!("{}", node.name);
     if node.edges.is_empty() {
         println!(" -> ()");
@@ -118,7 +146,7 @@ fn print_node<T>(node: &Node<T>) {
     }
 }
 
-fn print_edge<T>(edge: &Edge<T>) -> ActResult {
+pub fn print_edge<T>(edge: &Edge<T>) -> ActResult {
     let node = edge.0.borrow();
     println!("{}", node.name);
     if node.edges.is_empty() {
@@ -132,7 +160,7 @@ fn print_edge<T>(edge: &Edge<T>) -> ActResult {
 }
 
 // TODO: At the moment function can work only from graph root. Update to avoid this problem.
-fn depth_first_topological_sort<T>(root: Link<T>) -> VecDeque<Link<T>> {
+pub fn depth_first_topological_sort<T>(root: Link<T>) -> VecDeque<Link<T>> {
     // false - marked as temporary / true - marked as permanent.
     let marked: &mut HashMap<NodeName, (Link<T>, bool)> = &mut HashMap::new();
     // Sorted nodes.
@@ -185,16 +213,26 @@ fn visit<T>(
     sorted.push_front(node);
 }
 
-fn dijkstra<T>(root: Link<T>) -> (HashMap<NodeName, Weight>, HashMap<NodeName, Option<Link<T>>>)
+pub fn dijkstra<T>(root: Link<T>) -> ShortestPaths<T>
+where
+    T: Clone + Eq,
+{
+    dijkstra_view(&GraphView::new(root))
+}
+
+pub fn dijkstra_view<T>(view: &GraphView<T>) -> ShortestPaths<T>
 where
     T: Clone + Eq,
 {
+    let Some(root) = view.root() else {
+        return (HashMap::new(), HashMap::new());
+    };
     let mut processed: HashSet<NodeName> = HashSet::new();
     let nodes: RefCell<HashMap<NodeName, Link<T>>> = RefCell::new(HashMap::new());
     let costs: RefCell<HashMap<NodeName, Weight>> = RefCell::new(HashMap::new());
     let parents: RefCell<HashMap<NodeName, Option<Link<T>>>> = RefCell::new(HashMap::new());
 
-    root.borrow().traverse_breadth_first(
+    view.traverse_breadth_first(
         &|edge| -> ActResult {
             nodes.borrow_mut().insert(edge.0.borrow().name.clone(), edge.0.clone());
             costs.borrow_mut().insert(edge.0.borrow().name.clone(), Weight::MAX);
@@ -209,10 +247,10 @@ where
     let costs: &mut HashMap<NodeName, Weight> = &mut costs.borrow_mut();
     let parents: &mut HashMap<NodeName, Option<Link<T>>> = &mut parents.borrow_mut();
 
-    while let Some(closest_node_name) = find_closest_node::<NodeName>(costs, &processed) {
+    while let Some(closest_node_name) = find_closest_node(costs, &processed) {
         let closest_node: Link<T> = nodes.get(&closest_node_name).unwrap().clone();
         let cost = *costs.get_mut(&closest_node_name).unwrap();
-        let edges = closest_node.borrow().edges.clone();
+        let edges = view.edges(&closest_node.borrow());
         for edge in edges {
             let name = edge.0.borrow().name.clone();
             let new_cost = cost + edge.1;
@@ -228,7 +266,7 @@ where
     (costs.clone(), parents.clone())
 }
 
-fn find_closest_node<T>(
+fn find_closest_node(
     costs: &HashMap<NodeName, Weight>,
     processed: &HashSet<NodeName>,
 ) -> Option<NodeName> {
@@ -246,16 +284,26 @@ fn find_closest_node<T>(
     Some(closest_node_name)
 }
 
-fn bellman_ford<T>(root: Link<T>) -> (HashMap<NodeName, Weight>, HashMap<NodeName, Option<Link<T>>>)
+pub fn bellman_ford<T>(root: Link<T>) -> ShortestPaths<T>
+where
+    T: Clone + Eq,
+{
+    bellman_ford_view(&GraphView::new(root))
+}
+
+pub fn bellman_ford_view<T>(view: &GraphView<T>) -> ShortestPaths<T>
 where
     T: Clone + Eq,
 {
+    let Some(root) = view.root() else {
+        return (HashMap::new(), HashMap::new());
+    };
     let nodes_len: RefCell<usize> = RefCell::new(0);
     let costs: Rc<RefCell<HashMap<NodeName, Weight>>> = Rc::new(RefCell::new(HashMap::new()));
     let parents: RefCell<HashMap<NodeName, Option<Link<T>>>> = RefCell::new(HashMap::new());
 
     // Count nodes length and initialize hash maps.
-    root.borrow().traverse_breadth_first(
+    view.traverse_breadth_first(
         &|edge| -> ActResult {
             *nodes_len.borrow_mut() += 1;
             costs.borrow_mut().insert(edge.0.borrow().name.clone(), Weight::MAX);
@@ -269,7 +317,7 @@ where
 
     // TODO: Is it really need?
     for _ in 0..*nodes_len.borrow_mut() - 1 {
-        root.borrow().traverse_breadth_first(
+        view.traverse_breadth_first(
             &|edge| -> ActResult {
                 let parent_name = edge.0.borrow().name.clone();
                 let mut costs_ = costs.borrow_mut();
@@ -278,7 +326,7 @@ where
                 let g_parent_cost: Weight = *costs_.get(&parent_name).unwrap();
 
                 // Check each child.
-                for child in edge.0.borrow().edges.iter() {
+                for child in view.edges(&edge.0.borrow()).iter() {
                     let child_name = child.0.borrow().name.clone();
                     // Child cost from storage.
                     let g_child_cost: Weight = *costs_.get(&child_name).unwrap();
@@ -428,7 +476,7 @@ mod tests {
         }
     }
 
-    fn gen_graph() -> Link<u8> {
+    pub(crate) fn gen_graph() -> Link<u8> {
         let r = Node::new(String::from("R"), 0);
         let a = Node::new(String::from("A"), 1);
         let b = Node::new(String::from("B"), 2);
@@ -448,7 +496,7 @@ mod tests {
         r
     }
 
-    fn gen_graph_bellman() -> Link<u8> {
+    pub(crate) fn gen_graph_bellman() -> Link<u8> {
         let r = Node::new(String::from("R"), 0);
         let a = Node::new(String::from("A"), 1);
         let b = Node::new(String::from("B"), 2);
@@ -0,0 +1,272 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::{ActResult, FrozenPaths, Link, Node, NodeName, ShortestPaths, Weight};
+
+// Edge to neighbor by its name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerdeEdge {
+    pub to: NodeName,
+    pub weight: Weight,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerdeNode<T> {
+    pub name: NodeName,
+    pub data: T,
+    pub edges: Vec<SerdeEdge>,
+}
+
+// Graph reachable from root where links are replaced with node names.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerdeGraph<T> {
+    pub root: NodeName,
+    pub nodes: Vec<SerdeNode<T>>,
+}
+
+// Shortest path result for single node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerdePath {
+    pub cost: Weight,
+    pub parent: Option<NodeName>,
+}
+
+pub type SerdePaths = BTreeMap<NodeName, SerdePath>;
+
+#[derive(Debug, PartialEq)]
+pub enum SerdeGraphError {
+    DuplicateNode(NodeName),
+    UnknownNode(NodeName),
+}
+
+impl fmt::Display for SerdeGraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SerdeGraphError::DuplicateNode(name) => write!(f, "duplicate node {}", name),
+            SerdeGraphError::UnknownNode(name) => write!(f, "unknown node {}", name),
+        }
+    }
+}
+
+impl std::error::Error for SerdeGraphError {}
+
+impl<T> From<&Node<T>> for SerdeNode<T>
+where
+    T: Clone,
+{
+    fn from(n: &Node<T>) -> Self {
+        let edges = n
+            .edges
+            .iter()
+            .map(|edge| SerdeEdge {
+                to: edge.0.borrow().name.clone(),
+                weight: edge.1,
+            })
+            .collect();
+        Self {
+            name: n.name.clone(),
+            data: n.data.clone(),
+            edges,
+        }
+    }
+}
+
+// Node is serialized alone with edges as neighbor names, so links never recurse.
+impl<T> Serialize for Node<T>
+where
+    T: Clone + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        SerdeNode::from(self).serialize(serializer)
+    }
+}
+
+impl<T> SerdeGraph<T>
+where
+    T: Clone,
+{
+    // Collect all nodes reachable from root in breadth first order.
+    pub fn new(root: Link<T>) -> Self {
+        let nodes: RefCell<Vec<SerdeNode<T>>> = RefCell::new(Vec::new());
+        root.borrow().traverse_breadth_first(
+            &|edge| -> ActResult {
+                nodes.borrow_mut().push(SerdeNode::from(&*edge.0.borrow()));
+                ActResult::Ok
+            },
+            &mut HashSet::new(),
+        );
+        Self {
+            root: root.borrow().name.clone(),
+            nodes: nodes.into_inner(),
+        }
+    }
+
+    // Restore graph and return its root.
+    // Every node is created once, so edges to the same node share one link as in original graph.
+    pub fn into_graph(self) -> Result<Link<T>, SerdeGraphError> {
+        let mut links: HashMap<NodeName, Link<T>> = HashMap::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            if links.contains_key(&node.name) {
+                return Err(SerdeGraphError::DuplicateNode(node.name.clone()));
+            }
+            links.insert(node.name.clone(), Node::new(node.name.clone(), node.data.clone()));
+        }
+        for node in &self.nodes {
+            let mut link = links[&node.name].borrow_mut();
+            for edge in &node.edges {
+                let neighbor = links
+                    .get(&edge.to)
+                    .ok_or_else(|| SerdeGraphError::UnknownNode(edge.to.clone()))?;
+                link.add_edge(Rc::clone(neighbor), edge.weight);
+            }
+        }
+        links.remove(&self.root).ok_or(SerdeGraphError::UnknownNode(self.root))
+    }
+}
+
+// Convert dijkstra or bellman_ford result to plain map which can be serialized.
+pub fn serde_paths<T>(paths: &ShortestPaths<T>) -> SerdePaths {
+    let (costs, parents) = paths;
+    costs
+        .iter()
+        .map(|(name, cost)| {
+            let parent = parents.get(name).cloned().flatten().map(|p| p.borrow().name.clone());
+            (
+                name.clone(),
+                SerdePath {
+                    cost: *cost,
+                    parent,
+                },
+            )
+        })
+        .collect()
+}
+
+// Same as serde_paths but for frozen graph results.
+pub fn serde_frozen_paths(paths: &FrozenPaths) -> SerdePaths {
+    let (costs, parents) = paths;
+    costs
+        .iter()
+        .map(|(name, cost)| {
+            let parent = parents.get(name).cloned().flatten();
+            (
+                name.clone(),
+                SerdePath {
+                    cost: *cost,
+                    parent,
+                },
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::collections::HashSet;
+    use std::rc::Rc;
+
+    use crate::serialization::*;
+    use crate::tests::{gen_graph, gen_graph_bellman};
+    use crate::{bellman_ford, dijkstra, FrozenGraph};
+
+    #[test]
+    fn test_graph_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let root = gen_graph();
+        let json = serde_json::to_string(&SerdeGraph::new(root.clone()))?;
+        let restored = serde_json::from_str::<SerdeGraph<u8>>(&json)?.into_graph()?;
+        assert_eq!(SerdeGraph::new(restored.clone()), SerdeGraph::new(root.clone()));
+        assert_eq!(dijkstra(restored.clone()).0, dijkstra(root).0);
+
+        // A has two incoming edges (from R and F) and both should point to the same node.
+        let a = Rc::clone(&restored.borrow().edges[0].0);
+        let f_checked: Cell<bool> = Cell::new(false);
+        restored.borrow().traverse_depth_first(
+            &|node| {
+                if node.name == "F" {
+                    assert!(Rc::ptr_eq(&node.edges[0].0, &a));
+                    f_checked.set(true);
+                }
+            },
+            &mut HashSet::new(),
+        );
+        assert!(f_checked.get());
+        Ok(())
+    }
+
+    #[test]
+    fn test_node_serialize() -> Result<(), Box<dyn std::error::Error>> {
+        let root = gen_graph();
+        assert_eq!(
+            serde_json::to_string(&*root.borrow())?,
+            r#"{"name":"R","data":0,"edges":[{"to":"A","weight":1},{"to":"B","weight":9}]}"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_graph_errors() {
+        let graph: SerdeGraph<u8> = SerdeGraph {
+            root: String::from("R"),
+            nodes: vec![SerdeNode {
+                name: String::from("R"),
+                data: 0,
+                edges: vec![SerdeEdge {
+                    to: String::from("A"),
+                    weight: 1,
+                }],
+            }],
+        };
+        assert_eq!(graph.into_graph().err(), Some(SerdeGraphError::UnknownNode(String::from("A"))));
+
+        let node = SerdeNode {
+            name: String::from("R"),
+            data: 0,
+            edges: vec![],
+        };
+        let graph: SerdeGraph<u8> = SerdeGraph {
+            root: String::from("R"),
+            nodes: vec![node.clone(), node],
+        };
+        assert_eq!(
+            graph.into_graph().err(),
+            Some(SerdeGraphError::DuplicateNode(String::from("R")))
+        );
+    }
+
+    #[test]
+    fn test_paths_serialize() -> Result<(), Box<dyn std::error::Error>> {
+        let paths = serde_paths(&dijkstra(gen_graph()));
+        assert_eq!(
+            paths["G"],
+            SerdePath {
+                cost: 4,
+                parent: Some(String::from("A"))
+            }
+        );
+        assert_eq!(
+            paths["R"],
+            SerdePath {
+                cost: 0,
+                parent: None
+            }
+        );
+        let json = serde_json::to_string(&paths)?;
+        assert_eq!(serde_json::from_str::<SerdePaths>(&json)?, paths);
+
+        let frozen = FrozenGraph::new(gen_graph());
+        assert_eq!(serde_frozen_paths(&frozen.dijkstra("R").unwrap()), paths);
+
+        let paths = serde_paths(&bellman_ford(gen_graph_bellman()));
+        let json = serde_json::to_string(&paths)?;
+        assert_eq!(serde_json::from_str::<SerdePaths>(&json)?, paths);
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::rc::Rc;
+
+use crate::{ActResult, Edge, Link, Node, NodeName};
+
+type NodeFilter<'a, T> = Box<dyn Fn(&Node<T>) -> bool + 'a>;
+// Edge filter receives node which edge starts from.
+type EdgeFilter<'a, T> = Box<dyn Fn(&Node<T>, &Edge<T>) -> bool + 'a>;
+
+// Graph reachable from root where only nodes and edges passing filters are visible.
+// View doesn't copy the graph; filters are checked every time edges are requested.
+pub struct GraphView<'a, T> {
+    root: Link<T>,
+    node_filters: Vec<NodeFilter<'a, T>>,
+    edge_filters: Vec<EdgeFilter<'a, T>>,
+}
+
+impl<'a, T> GraphView<'a, T> {
+    // View of the whole graph.
+    pub fn new(root: Link<T>) -> Self {
+        Self {
+            root,
+            node_filters: Vec::new(),
+            edge_filters: Vec::new(),
+        }
+    }
+
+    // View of induced subgraph: only nodes with given names and edges between them.
+    pub fn induced(root: Link<T>, names: HashSet<NodeName>) -> Self {
+        Self::new(root).filter_nodes(move |node| names.contains(&node.name))
+    }
+
+    // Add one more node filter; node is visible only if it passes all of them.
+    pub fn filter_nodes<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&Node<T>) -> bool + 'a,
+    {
+        self.node_filters.push(Box::new(filter));
+        self
+    }
+
+    // Add one more edge filter; edge is visible only if it passes all of them.
+    pub fn filter_edges<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&Node<T>, &Edge<T>) -> bool + 'a,
+    {
+        self.edge_filters.push(Box::new(filter));
+        self
+    }
+
+    pub fn contains(&self, node: &Node<T>) -> bool {
+        self.node_filters.iter().all(|filter| filter(node))
+    }
+
+    // Root or None if root is filtered out and so view is empty.
+    pub fn root(&self) -> Option<Link<T>> {
+        if !self.contains(&self.root.borrow()) {
+            return None;
+        }
+        Some(Rc::clone(&self.root))
+    }
+
+    // Visible edges of the node; edges to filtered out nodes are hidden too.
+    pub fn edges(&self, node: &Node<T>) -> Vec<Edge<T>> {
+        node.edges
+            .iter()
+            .filter(|edge| {
+                self.edge_filters.iter().all(|filter| filter(node, edge))
+                    && self.contains(&edge.0.borrow())
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub fn traverse_depth_first<F>(&self, act: &F, seen: &mut HashSet<NodeName>)
+    where
+        F: Fn(&Node<T>),
+    {
+        if let Some(root) = self.root() {
+            self.visit_depth_first(root, act, seen)
+        }
+    }
+
+    fn visit_depth_first<F>(&self, node: Link<T>, act: &F, seen: &mut HashSet<NodeName>)
+    where
+        F: Fn(&Node<T>),
+    {
+        let node = node.borrow();
+        if seen.contains(&node.name) {
+            return;
+        }
+        act(&node);
+        seen.insert(node.name.clone());
+        for edge in self.edges(&node) {
+            self.visit_depth_first(edge.0, act, seen);
+        }
+    }
+
+    // Same as Node::traverse_breadth_first, root is passed with zero weight.
+    pub fn traverse_breadth_first<F>(&self, act: &F, seen: &mut HashSet<NodeName>)
+    where
+        F: Fn(&Edge<T>) -> ActResult,
+    {
+        let Some(root) = self.root() else {
+            return;
+        };
+        let mut stack: VecDeque<Edge<T>> = VecDeque::new();
+        stack.push_back((root, 0));
+        while let Some(edge) = stack.pop_front() {
+            let node = edge.0.borrow();
+            if seen.contains(&node.name) {
+                continue;
+            }
+            let res = act(&edge);
+            if let ActResult::Stop = res {
+                return;
+            };
+            seen.insert(node.name.clone());
+            stack.extend(self.edges(&node));
+        }
+    }
+}
+
+impl<'a, T> GraphView<'a, T>
+where
+    T: Clone,
+{
+    // Copy visible part of the graph into new graph and return its root.
+    pub fn extract(&self) -> Option<Link<T>> {
+        let root = self.root()?;
+        let mut links: HashMap<NodeName, Link<T>> = HashMap::new();
+        let mut queue: VecDeque<Link<T>> = VecDeque::from([root]);
+        let mut order: Vec<Link<T>> = Vec::new();
+        // Create copies of all visible nodes first, so edges can point to them.
+        while let Some(node) = queue.pop_front() {
+            let name = node.borrow().name.clone();
+            if links.contains_key(&name) {
+                continue;
+            }
+            links.insert(name, Node::new(node.borrow().name.clone(), node.borrow().data.clone()));
+            queue.extend(self.edges(&node.borrow()).into_iter().map(|edge| edge.0));
+            order.push(node);
+        }
+        for node in &order {
+            let node = node.borrow();
+            let mut copy = links[&node.name].borrow_mut();
+            for (to, weight) in self.edges(&node) {
+                copy.add_edge(Rc::clone(&links[&to.borrow().name]), weight);
+            }
+        }
+        Some(Rc::clone(&links[&self.root.borrow().name]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::{HashMap, HashSet};
+
+    use crate::tests::gen_graph;
+    use crate::view::*;
+    use crate::{
+        bellman_ford_view, breadth_first_search_view, dijkstra, dijkstra_view, print_node, Weight,
+    };
+
+    #[test]
+    fn test_view_traverse() {
+        let root = gen_graph();
+        let names = |view: &GraphView<u8>| -> Vec<NodeName> {
+            let names: RefCell<Vec<NodeName>> = RefCell::new(Vec::new());
+            view.traverse_depth_first(
+                &|node| names.borrow_mut().push(node.name.clone()),
+                &mut HashSet::new(),
+            );
+            names.into_inner()
+        };
+
+        assert_eq!(
+            names(&GraphView::new(root.clone())),
+            vec!["R", "A", "C", "D", "G", "B", "E", "F"]
+        );
+        let view = GraphView::new(root.clone()).filter_edges(|_, edge| edge.1 < 6);
+        assert_eq!(names(&view), vec!["R", "A", "G"]);
+        let view = GraphView::new(root.clone()).filter_nodes(|node| node.data % 2 == 0);
+        assert_eq!(names(&view), vec!["R", "B", "D"]);
+        let view = GraphView::new(root.clone()).filter_nodes(|node| node.name != "R");
+        assert_eq!(names(&view), Vec::<NodeName>::new());
+        assert!(view.root().is_none());
+        assert!(view.extract().is_none());
+
+        print!("\n\n Filtered Depth First Traverse\n");
+        GraphView::new(root)
+            .filter_edges(|_, edge| edge.1 < 6)
+            .traverse_depth_first(&print_node, &mut HashSet::new());
+    }
+
+    #[test]
+    fn test_view_shortest_paths() {
+        let root = gen_graph();
+        assert_eq!(dijkstra_view(&GraphView::new(root.clone())).0, dijkstra(root.clone()).0);
+
+        // Without cheap R -> A edge A can be reached only through B, E and F.
+        let view = GraphView::new(root.clone())
+            .filter_edges(|from, edge| !(from.name == "R" && edge.0.borrow().name == "A"));
+        let (costs, parents) = dijkstra_view(&view);
+        assert_eq!(costs["A"], 22);
+        assert_eq!(parents["A"].as_ref().unwrap().borrow().name, "F");
+        assert_eq!(bellman_ford_view(&view).0, costs);
+        assert_eq!(breadth_first_search_view(&view, "G"), Some(7));
+
+        let view = GraphView::induced(
+            root.clone(),
+            HashSet::from([String::from("R"), String::from("A"), String::from("G")]),
+        );
+        let (costs, _) = dijkstra_view(&view);
+        assert_eq!(
+            costs,
+            HashMap::from([
+                (String::from("R"), 0),
+                (String::from("A"), 1),
+                (String::from("G"), 4)
+            ])
+        );
+        assert_eq!(breadth_first_search_view(&view, "B"), None);
+
+        // Extracted graph is independent from original one.
+        let extracted = view.extract().unwrap();
+        let costs: HashMap<NodeName, Weight> = dijkstra(extracted.clone()).0;
+        assert_eq!(costs.len(), 3);
+        extracted.borrow_mut().edges.clear();
+        assert_eq!(dijkstra(root).0.len(), 8);
+    }
+}
@@ -0,0 +1,62 @@
+use std::cmp::Ordering;
+
+// Comparator which defines order of elements.
+pub type Compare<'a, T> = dyn FnMut(&T, &T) -> Ordering + 'a;
+// Range [lt, gt] of elements which are in their final place after partitioning.
+// It is empty (gt + 1 == lt) if partition scheme doesn't gather pivot values together.
+pub type PivotRange = (usize, usize);
+
+// Step of sorting algorithm; indexes point to positions in sorted slice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortEvent {
+    Compare(usize, usize),
+    Swap(usize, usize),
+    // Element was moved into a new buffer; reported only by sorts which aren't in place.
+    Move,
+    // Range [start, stop] was partitioned around pivot range.
+    Partition {
+        start: usize,
+        stop: usize,
+        pivot_range: PivotRange,
+    },
+}
+
+// Hook to build step by step visualizations or collect statistics.
+pub trait SortObserver {
+    fn observe(&mut self, event: SortEvent);
+}
+
+impl<F> SortObserver for F
+where
+    F: FnMut(SortEvent),
+{
+    fn observe(&mut self, event: SortEvent) {
+        self(event)
+    }
+}
+
+// Comparator with observer; sorts compare and swap elements only through it.
+pub struct SortContext<'a, 'b, T> {
+    cmp: &'a mut Compare<'b, T>,
+    observer: &'a mut dyn SortObserver,
+}
+
+impl<'a, 'b, T> SortContext<'a, 'b, T> {
+    pub fn new(cmp: &'a mut Compare<'b, T>, observer: &'a mut dyn SortObserver) -> Self {
+        Self { cmp, observer }
+    }
+
+    pub fn compare(&mut self, v: &[T], i: usize, j: usize) -> Ordering {
+        self.observer.observe(SortEvent::Compare(i, j));
+        (self.cmp)(&v[i], &v[j])
+    }
+
+    pub fn swap(&mut self, v: &mut [T], i: usize, j: usize) {
+        self.observer.observe(SortEvent::Swap(i, j));
+        v.swap(i, j)
+    }
+
+    pub fn observe(&mut self, event: SortEvent) {
+        self.observer.observe(event)
+    }
+}
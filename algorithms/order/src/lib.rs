@@ -0,0 +1,172 @@
+use std::cmp::Ordering;
+
+mod context;
+
+pub use context::{Compare, PivotRange, SortContext, SortEvent, SortObserver};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Ascending,
+    Descending,
+}
+
+impl Direction {
+    pub fn apply(self, ordering: Ordering) -> Ordering {
+        match self {
+            Direction::Ascending => ordering,
+            Direction::Descending => ordering.reverse(),
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        match self {
+            Direction::Ascending => Direction::Descending,
+            Direction::Descending => Direction::Ascending,
+        }
+    }
+}
+
+// Comparator is Send and Sync, so the same order can be used by parallel sorting.
+type Comparator<'a, T> = Box<dyn Fn(&T, &T) -> Ordering + Send + Sync + 'a>;
+
+// Order in which elements should be sorted: natural, by key or by comparator.
+// Orders can be chained with then, so "status asc, date desc" is
+// Order::by_key(|r| r.status).then(Order::by_key(|r| r.date).reverse()).
+pub struct Order<'a, T> {
+    cmp: Comparator<'a, T>,
+    // Some if order is natural order of T, so algorithms which don't compare can use it.
+    direction: Option<Direction>,
+}
+
+impl<'a, T> Order<'a, T>
+where
+    T: 'a,
+{
+    pub fn ascending() -> Self
+    where
+        T: Ord,
+    {
+        Self::natural(Direction::Ascending)
+    }
+
+    pub fn descending() -> Self
+    where
+        T: Ord,
+    {
+        Self::natural(Direction::Descending)
+    }
+
+    pub fn natural(direction: Direction) -> Self
+    where
+        T: Ord,
+    {
+        Self {
+            cmp: Box::new(move |a: &T, b: &T| direction.apply(a.cmp(b))),
+            direction: Some(direction),
+        }
+    }
+
+    pub fn by<F>(cmp: F) -> Self
+    where
+        F: Fn(&T, &T) -> Ordering + Send + Sync + 'a,
+    {
+        Self {
+            cmp: Box::new(cmp),
+            direction: None,
+        }
+    }
+
+    // Ascending order of keys; key is computed on every comparison.
+    pub fn by_key<K, F>(f: F) -> Self
+    where
+        K: Ord,
+        F: Fn(&T) -> K + Send + Sync + 'a,
+    {
+        Self::by(move |a, b| f(a).cmp(&f(b)))
+    }
+
+    pub fn reverse(self) -> Self {
+        let cmp = self.cmp;
+        Self {
+            cmp: Box::new(move |a: &T, b: &T| cmp(a, b).reverse()),
+            direction: self.direction.map(Direction::reverse),
+        }
+    }
+
+    // Elements equal by this order are ordered by next one.
+    pub fn then(self, next: Order<'a, T>) -> Self {
+        let cmp = self.cmp;
+        Self::by(move |a, b| cmp(a, b).then_with(|| next.compare(a, b)))
+    }
+
+    pub fn compare(&self, a: &T, b: &T) -> Ordering {
+        (self.cmp)(a, b)
+    }
+
+    // Direction of natural order, None for orders by key or comparator.
+    pub fn direction(&self) -> Option<Direction> {
+        self.direction
+    }
+}
+
+impl<'a, T> From<Direction> for Order<'a, T>
+where
+    T: Ord + 'a,
+{
+    fn from(direction: Direction) -> Self {
+        Self::natural(direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_natural_order() {
+        let mut v: Vec<u128> = vec![5, 1, 4, 1];
+        v.sort_by(|a, b| Order::ascending().compare(a, b));
+        assert_eq!(v, vec![1, 1, 4, 5]);
+        let order: Order<u128> = Order::descending();
+        v.sort_by(|a, b| order.compare(a, b));
+        assert_eq!(v, vec![5, 4, 1, 1]);
+        assert_eq!(order.direction(), Some(Direction::Descending));
+        assert_eq!(order.reverse().direction(), Some(Direction::Ascending));
+        assert_eq!(Order::<u128>::by_key(|x| *x).direction(), None);
+    }
+
+    #[test]
+    fn test_multi_key_order() {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Row {
+            status: &'static str,
+            date: u32,
+        }
+        let row = |status, date| Row { status, date };
+        let mut rows: Vec<Row> = vec![
+            row("open", 20240102),
+            row("closed", 20240101),
+            row("open", 20240305),
+            row("closed", 20240410),
+            row("open", 20240101),
+        ];
+        // Status asc, date desc.
+        let order =
+            Order::by_key(|r: &Row| r.status).then(Order::by_key(|r: &Row| r.date).reverse());
+        rows.sort_by(|a, b| order.compare(a, b));
+        assert_eq!(
+            rows,
+            vec![
+                row("closed", 20240410),
+                row("closed", 20240101),
+                row("open", 20240305),
+                row("open", 20240102),
+                row("open", 20240101),
+            ]
+        );
+
+        let order = Order::by(|a: &Row, b: &Row| a.date.cmp(&b.date)).reverse();
+        rows.sort_by(|a, b| order.compare(a, b));
+        assert_eq!(rows[0], row("closed", 20240410));
+    }
+}
@@ -0,0 +1,144 @@
+use std::cmp::Ordering;
+
+use selection_sort::sift_down;
+
+use crate::{Partition, PivotRange, SortContext};
+
+// Ranges of this length or shorter are sorted with insertion sort by default.
+const INSERTION_THRESHOLD: usize = 16;
+
+// Quicksort which falls back to heapsort when ranges are split too many times
+// (more than 2 * log2(n)), so adversarial inputs can't make it quadratic.
+// Short ranges are finished with insertion sort.
+pub struct Introsort<P> {
+    partition: P,
+    insertion_threshold: usize,
+}
+
+impl<P> Introsort<P> {
+    pub fn new(partition: P) -> Self {
+        Self {
+            partition,
+            insertion_threshold: INSERTION_THRESHOLD,
+        }
+    }
+
+    pub fn with_insertion_threshold(mut self, insertion_threshold: usize) -> Self {
+        self.insertion_threshold = insertion_threshold;
+        self
+    }
+}
+
+impl<T, P> Partition<T> for Introsort<P>
+where
+    P: Partition<T>,
+{
+    fn do_part(
+        &self,
+        v: &mut [T],
+        start: usize,
+        stop: usize,
+        ctx: &mut SortContext<T>,
+    ) -> PivotRange {
+        self.partition.do_part(v, start, stop, ctx)
+    }
+
+    fn do_stack(
+        &self,
+        stack: &mut Vec<(usize, usize)>,
+        ss: (usize, usize),
+        pivot_range: PivotRange,
+    ) {
+        self.partition.do_stack(stack, ss, pivot_range)
+    }
+
+    fn pivot_index(&self, start: usize, stop: usize) -> usize {
+        self.partition.pivot_index(start, stop)
+    }
+
+    fn do_finish(
+        &self,
+        v: &mut [T],
+        ss: (usize, usize),
+        depth: usize,
+        ctx: &mut SortContext<T>,
+    ) -> bool {
+        if ss.1 - ss.0 < self.insertion_threshold {
+            insertion_sort(v, ss.0, ss.1, ctx);
+            return true;
+        }
+        if depth > max_depth(v.len()) {
+            heapsort(v, ss.0, ss.1, ctx);
+            return true;
+        }
+        self.partition.do_finish(v, ss, depth, ctx)
+    }
+}
+
+// 2 * log2(n).
+fn max_depth(n: usize) -> usize {
+    2 * n.checked_ilog2().unwrap_or(0) as usize
+}
+
+fn insertion_sort<T>(v: &mut [T], start: usize, stop: usize, ctx: &mut SortContext<T>) {
+    for i in start + 1..=stop {
+        let mut j = i;
+        while j > start && ctx.compare(v, j - 1, j) == Ordering::Greater {
+            ctx.swap(v, j - 1, j);
+            j -= 1;
+        }
+    }
+}
+
+// Heapsort of [start, stop] range, indexes inside heap are relative to start.
+fn heapsort<T>(v: &mut [T], start: usize, stop: usize, ctx: &mut SortContext<T>) {
+    let len = stop - start + 1;
+    for root in (0..len / 2).rev() {
+        sift_down(v, start, root, len, ctx);
+    }
+    for end in (1..len).rev() {
+        // Move the biggest element to the end and restore heap for the rest.
+        ctx.swap(v, start, start + end);
+        sift_down(v, start, 0, end, ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::introsort::*;
+    use crate::tests::count_compares;
+    use crate::{quicksort, Hoare, Lomuto, Order};
+
+    #[test]
+    fn test_introsort() {
+        for n in [0, 1, 2, 15, 16, 17, 100, 1000] {
+            let input: Vec<u128> = (0..n).map(|i| (i * 7919 % 251) as u128).collect();
+            let mut expected = input.clone();
+            expected.sort();
+
+            let mut res = input.clone();
+            quicksort(&mut res, &Order::ascending(), Box::new(Introsort::new(Hoare {})));
+            assert_eq!(res, expected);
+            let mut res = input.clone();
+            quicksort(&mut res, &Order::ascending(), Box::new(Introsort::new(Lomuto {})));
+            assert_eq!(res, expected);
+
+            // Without insertion sort every range is partitioned or goes to heapsort.
+            expected.reverse();
+            let mut res = input.clone();
+            let introsort = Introsort::new(Lomuto {}).with_insertion_threshold(0);
+            quicksort(&mut res, &Order::descending(), Box::new(introsort));
+            assert_eq!(res, expected);
+        }
+    }
+
+    #[test]
+    fn test_introsort_adversarial() {
+        // Sorted input makes Lomuto quadratic as the last element is always the biggest one.
+        let n: usize = 2000;
+        let input: Vec<u128> = (0..n as u128).collect();
+        assert!(count_compares(&input, Box::new(Lomuto {})) > n * n / 4);
+        // Heapsort makes at most 2 * n * log2(n) comparisons, partitions before it 2 * n * log2(n) more.
+        assert!(count_compares(&input, Box::new(Introsort::new(Lomuto {}))) < 4 * n * 11);
+    }
+}
@@ -1,214 +1,374 @@
-trait Partition {
-    fn do_part(&self, v: &mut Vec<u128>, start: usize, stop: usize, asc: bool) -> usize;
+use std::cmp::Ordering;
+
+pub use order::{Compare, Direction, Order, PivotRange, SortContext, SortEvent, SortObserver};
+
+mod introsort;
+mod parallel;
+mod pivot;
+mod select;
+
+pub use introsort::Introsort;
+pub use parallel::{quicksort_parallel, quicksort_parallel_by, PARALLEL_CUTOFF};
+pub use pivot::{
+    First, Last, MedianOfMedians, MedianOfThree, Middle, Ninther, Pivot, Random, WithPivot,
+};
+pub use select::{median, select_nth, select_nth_by, top_k};
+
+pub trait Partition<T> {
+    fn do_part(
+        &self,
+        v: &mut [T],
+        start: usize,
+        stop: usize,
+        ctx: &mut SortContext<T>,
+    ) -> PivotRange;
+    // Push ranges around pivot range which still need sorting.
     fn do_stack(
         &self,
         stack: &mut Vec<(usize, usize)>,
         ss: (usize, usize),
-        pivot_comparison_index: usize,
-    );
+        pivot_range: PivotRange,
+    ) {
+        let (lt, gt) = pivot_range;
+        if lt > ss.0 + 1 {
+            stack.push((ss.0, lt - 1))
+        }
+        if gt + 1 < ss.1 {
+            stack.push((gt + 1, ss.1))
+        }
+    }
+    // Index of pivot which do_part partitions [start, stop] range around.
+    fn pivot_index(&self, start: usize, stop: usize) -> usize;
+    // Called before range partitioning; depth is count of partitions the range is produced by.
+    // Returns true if range is sorted other way and shouldn't be partitioned.
+    fn do_finish(
+        &self,
+        _v: &mut [T],
+        _ss: (usize, usize),
+        _depth: usize,
+        _ctx: &mut SortContext<T>,
+    ) -> bool {
+        false
+    }
+}
+
+// Not stable with any partition scheme: equal elements can be reordered.
+pub fn quicksort<T>(v: &mut [T], order: &Order<T>, partition: Box<dyn Partition<T>>) {
+    quicksort_by(v, |a, b| order.compare(a, b), partition)
 }
 
-fn quicksort(mut v: Vec<u128>, asc: bool, partition: Box<dyn Partition>) -> Vec<u128> {
-    let mut stack: Vec<(usize, usize)> = Vec::new();
-    stack.push((0, v.len() - 1));
+pub fn quicksort_by_key<T, K, F>(v: &mut [T], mut f: F, partition: Box<dyn Partition<T>>)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    quicksort_by(v, |a, b| f(a).cmp(&f(b)), partition)
+}
+
+pub fn quicksort_by<T, F>(v: &mut [T], cmp: F, partition: Box<dyn Partition<T>>)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    quicksort_observed(v, cmp, partition, &mut |_| {})
+}
 
-    while let Some(ss) = stack.pop() {
-        let pivot_comparison_index: usize = partition.do_part(&mut v, ss.0, ss.1, asc);
-        partition.do_stack(&mut stack, ss, pivot_comparison_index);
+// Same as quicksort_by but reports every comparison, swap and partition to observer.
+pub fn quicksort_observed<T, F>(
+    v: &mut [T],
+    mut cmp: F,
+    partition: Box<dyn Partition<T>>,
+    observer: &mut dyn SortObserver,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // Empty and single element slices are already sorted.
+    if v.len() < 2 {
+        return;
     }
+    let mut ctx = SortContext::new(&mut cmp, observer);
+    sort_range(v, (0, v.len() - 1), 0, partition.as_ref(), &mut ctx)
+}
 
-    println!();
-    v
+// Sort [ss.0, ss.1] range which is already depth partitions deep.
+pub(crate) fn sort_range<T>(
+    v: &mut [T],
+    ss: (usize, usize),
+    depth: usize,
+    partition: &dyn Partition<T>,
+    ctx: &mut SortContext<T>,
+) {
+    // Ranges to sort with their depth.
+    let mut stack: Vec<((usize, usize), usize)> = Vec::new();
+    stack.push((ss, depth));
+    // Ranges produced by the last partition.
+    let mut sub_ranges: Vec<(usize, usize)> = Vec::with_capacity(2);
+
+    while let Some((ss, depth)) = stack.pop() {
+        if partition.do_finish(v, ss, depth, ctx) {
+            continue;
+        }
+        let pivot_range: PivotRange = partition.do_part(v, ss.0, ss.1, ctx);
+        ctx.observe(SortEvent::Partition {
+            start: ss.0,
+            stop: ss.1,
+            pivot_range,
+        });
+        partition.do_stack(&mut sub_ranges, ss, pivot_range);
+        stack.extend(sub_ranges.drain(..).map(|sub_range| (sub_range, depth + 1)));
+    }
 }
 
-struct Hoare {}
+pub struct Hoare {}
 
-impl Partition for Hoare {
-    fn do_part(&self, v: &mut Vec<u128>, start: usize, stop: usize, asc: bool) -> usize {
-        let mid = (start + stop) / 2;
-        let pivot = v[mid];
-        let mut i = start.checked_sub(1);
-        let mut j = stop + 1;
+impl<T> Partition<T> for Hoare {
+    // Elements are split into [start, j] and [j + 1, stop] ranges, pivot isn't in its final place.
+    fn do_part(
+        &self,
+        v: &mut [T],
+        start: usize,
+        stop: usize,
+        ctx: &mut SortContext<T>,
+    ) -> PivotRange {
+        // Pivot can be moved by swaps, so track its index instead of copying it.
+        let mut pivot = Partition::<T>::pivot_index(self, start, stop);
+        // Next elements to check from the left and from the right.
+        // Pivot stops both scans, so they never leave [start, stop] range.
+        let mut i = start;
+        let mut j = stop;
         loop {
-            println!("\n{:?}", v);
-            println!("start : pivot={} i={:?} j={}", pivot, i, j);
-            loop {
-                if i.is_some() {
-                    i = i.unwrap().checked_add(1);
-                } else {
-                    i = Some(0);
-                }
-                println!("low : i={:?} v[i]={} pivot={}", i, v[i.unwrap()], pivot);
-                if asc && v[i.unwrap()] >= pivot {
-                    println!("break");
-                    break;
-                }
-                if !asc && v[i.unwrap()] <= pivot {
-                    println!("break");
-                    break;
-                }
+            while ctx.compare(v, i, pivot) == Ordering::Less {
+                i += 1;
             }
-            loop {
+            while ctx.compare(v, j, pivot) == Ordering::Greater {
                 j -= 1;
-                println!("high : j={} v[j]={} pivot={}", j, v[j], pivot);
-                if asc && v[j] <= pivot {
-                    println!("break");
-                    break;
-                }
-                if !asc && v[j] >= pivot {
-                    println!("break");
-                    break;
-                }
             }
-            println!("stop : i={:?} j={} v[i]={} v[j]={}", i, j, v[i.unwrap()], v[j]);
-            if i.unwrap() >= j {
-                println!("return");
-                return j;
+            if i >= j {
+                return (j + 1, j);
             }
-            println!("swap");
-            swap_force(v, i.unwrap(), j)
+            ctx.swap(v, i, j);
+            if pivot == i {
+                pivot = j
+            } else if pivot == j {
+                pivot = i
+            }
+            // After swap i < j, so j can't go below start.
+            i += 1;
+            j -= 1;
         }
     }
 
-    fn do_stack(
-        &self,
-        stack: &mut Vec<(usize, usize)>,
-        ss: (usize, usize),
-        pivot_comparison_index: usize,
-    ) {
-        println!("stack - {:?} {}", ss, pivot_comparison_index);
-        if pivot_comparison_index != 0 && pivot_comparison_index > ss.0 {
-            stack.push((ss.0, pivot_comparison_index))
-        }
-        if pivot_comparison_index + 1 < ss.1 {
-            stack.push((pivot_comparison_index + 1, ss.1))
-        }
+    fn pivot_index(&self, start: usize, stop: usize) -> usize {
+        (start + stop) / 2
     }
 }
 
-struct Lomuto {}
+pub struct Lomuto {}
 
-impl Partition for Lomuto {
-    fn do_part(&self, v: &mut Vec<u128>, start: usize, stop: usize, asc: bool) -> usize {
-        let pivot = v[stop];
+impl<T> Partition<T> for Lomuto {
+    fn do_part(
+        &self,
+        v: &mut [T],
+        start: usize,
+        stop: usize,
+        ctx: &mut SortContext<T>,
+    ) -> PivotRange {
+        // Pivot is the last element and it isn't moved until the end of partitioning.
+        let pivot = Partition::<T>::pivot_index(self, start, stop);
         // Numbers that are smaller than pivot go before (left) of this index or vice versa if decent.
         let mut pivot_comparison_index = start;
 
         for i in start..stop {
-            if asc && v[i] <= pivot {
-                swap_force(v, i, pivot_comparison_index);
-                pivot_comparison_index += 1
-            }
-            if !asc && v[i] >= pivot {
-                swap_force(v, i, pivot_comparison_index);
+            if ctx.compare(v, i, pivot) != Ordering::Greater {
+                ctx.swap(v, i, pivot_comparison_index);
                 pivot_comparison_index += 1
             }
         }
 
-        swap_force(v, pivot_comparison_index, stop);
+        ctx.swap(v, pivot_comparison_index, stop);
 
-        pivot_comparison_index
+        (pivot_comparison_index, pivot_comparison_index)
     }
 
-    fn do_stack(
+    fn pivot_index(&self, _start: usize, stop: usize) -> usize {
+        stop
+    }
+}
+
+// Dijkstra's three-way partition: elements smaller than pivot, equal to pivot and bigger than pivot.
+// Block of equal elements is never sorted again, so inputs with many duplicates stay fast.
+pub struct ThreeWay {}
+
+impl<T> Partition<T> for ThreeWay {
+    fn do_part(
         &self,
-        stack: &mut Vec<(usize, usize)>,
-        ss: (usize, usize),
-        pivot_comparison_index: usize,
-    ) {
-        println!("stack - {:?} {}", ss, pivot_comparison_index);
-        if pivot_comparison_index != 0 && pivot_comparison_index - 1 > ss.0 {
-            stack.push((ss.0, pivot_comparison_index - 1))
+        v: &mut [T],
+        start: usize,
+        stop: usize,
+        ctx: &mut SortContext<T>,
+    ) -> PivotRange {
+        let pivot = Partition::<T>::pivot_index(self, start, stop);
+        if pivot != start {
+            ctx.swap(v, pivot, start);
         }
-        if pivot_comparison_index + 1 < ss.1 {
-            stack.push((pivot_comparison_index + 1, ss.1))
+        // [start, lt) - smaller, [lt, i) - equal, [i, gt] - not checked yet, (gt, stop] - bigger.
+        // Equal block always starts with pivot value at lt.
+        let mut lt = start;
+        let mut i = start + 1;
+        let mut gt = stop;
+        while i <= gt {
+            match ctx.compare(v, i, lt) {
+                Ordering::Less => {
+                    ctx.swap(v, lt, i);
+                    lt += 1;
+                    i += 1;
+                }
+                Ordering::Greater => {
+                    ctx.swap(v, i, gt);
+                    gt -= 1;
+                }
+                Ordering::Equal => i += 1,
+            }
+        }
+        (lt, gt)
+    }
+
+    fn pivot_index(&self, start: usize, stop: usize) -> usize {
+        (start + stop) / 2
+    }
+}
+
+// Recursive quicksort which partitions slice in place and doesn't allocate.
+// Smaller side of every partition is sorted by recursive call and bigger one by the same call,
+// so recursion depth is O(log n) as the stack of iterative quicksort.
+// Result is identical to quicksort with the same partition scheme.
+pub fn quicksort_in_place<T>(v: &mut [T], order: &Order<T>, partition: Box<dyn Partition<T>>) {
+    quicksort_in_place_by(v, |a, b| order.compare(a, b), partition)
+}
+
+pub fn quicksort_in_place_by<T, F>(v: &mut [T], mut cmp: F, partition: Box<dyn Partition<T>>)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut observer = |_| {};
+    let mut ctx = SortContext::new(&mut cmp, &mut observer);
+    let len = v.len();
+    sort_in_place(v, 0, len, 0, partition.as_ref(), &mut ctx)
+}
+
+// Sort [start, end) range; half-open range can't underflow on empty sides.
+// Sides are taken from pivot range directly instead of do_stack, which needs a vector.
+fn sort_in_place<T>(
+    v: &mut [T],
+    mut start: usize,
+    mut end: usize,
+    mut depth: usize,
+    partition: &dyn Partition<T>,
+    ctx: &mut SortContext<T>,
+) {
+    while end - start > 1 {
+        let ss = (start, end - 1);
+        if partition.do_finish(v, ss, depth, ctx) {
+            return;
+        }
+        let (lt, gt): PivotRange = partition.do_part(v, ss.0, ss.1, ctx);
+        depth += 1;
+        if lt - start < end - (gt + 1) {
+            sort_in_place(v, start, lt, depth, partition, ctx);
+            start = gt + 1;
+        } else {
+            sort_in_place(v, gt + 1, end, depth, partition, ctx);
+            end = lt;
         }
     }
 }
 
-fn quicksort_recursive(mut v: Vec<u128>, asc: bool) -> Vec<u128> {
+// Not stable: pivot is taken from the middle and swap_remove moves the last element.
+pub fn quicksort_recursive<T>(v: Vec<T>, order: &Order<T>) -> Vec<T> {
+    quicksort_recursive_observed(v, order, &mut |_| {})
+}
+
+// Same as quicksort_recursive, but swaps and moves into sub-arrays are reported to observer.
+// Indexes of swaps are relative to the sub-array; compares aren't reported, as compared
+// element is already moved out of its array.
+pub fn quicksort_recursive_observed<T>(
+    mut v: Vec<T>,
+    order: &Order<T>,
+    observer: &mut dyn SortObserver,
+) -> Vec<T> {
     if v.len() <= 1 {
         return v;
     }
 
     if v.len() == 2 {
-        swap(&mut v, 0, 1, asc);
+        swap(&mut v, 0, 1, order, observer);
         return v;
     }
 
     let pivot_index = (v.len() - 1) / 2;
-    let pivot = v[pivot_index];
-    v.swap_remove(pivot_index);
-    let mut smaller: Vec<u128> = Vec::new();
-    let mut larger: Vec<u128> = Vec::new();
+    let pivot = v.swap_remove(pivot_index);
+    let mut smaller: Vec<T> = Vec::new();
+    let mut equal: Vec<T> = Vec::new();
+    let mut larger: Vec<T> = Vec::new();
     for x in v {
-        choose_sub_array(x, pivot, &mut smaller, &mut larger, asc)
+        choose_sub_array(x, &pivot, &mut smaller, &mut equal, &mut larger, order);
+        observer.observe(SortEvent::Move);
     }
 
-    let sorted_smaller = quicksort_recursive(smaller, asc);
-    let sorted_larger = quicksort_recursive(larger, asc);
+    let sorted_smaller = quicksort_recursive_observed(smaller, order, observer);
+    let sorted_larger = quicksort_recursive_observed(larger, order, observer);
 
-    let mut result: Vec<u128> = Vec::new();
+    let mut result: Vec<T> = Vec::new();
     result.extend(sorted_smaller);
     result.push(pivot);
+    result.extend(equal);
     result.extend(sorted_larger);
 
     result
 }
 
-fn choose_sub_array(
-    x: u128,
-    pivot: u128,
-    smaller: &mut Vec<u128>,
-    larger: &mut Vec<u128>,
-    asc: bool,
+// Elements equal to pivot are kept aside, so they aren't sorted again.
+fn choose_sub_array<T>(
+    x: T,
+    pivot: &T,
+    smaller: &mut Vec<T>,
+    equal: &mut Vec<T>,
+    larger: &mut Vec<T>,
+    order: &Order<T>,
 ) {
-    if asc {
-        if x > pivot {
-            larger.push(x);
-        } else {
-            smaller.push(x);
-        }
-    } else {
-        if x > pivot {
-            smaller.push(x);
-        } else {
-            larger.push(x);
-        }
+    match order.compare(&x, pivot) {
+        Ordering::Equal => equal.push(x),
+        Ordering::Greater => larger.push(x),
+        Ordering::Less => smaller.push(x),
     }
 }
 
-fn swap_force(v: &mut Vec<u128>, i1: usize, i2: usize) {
-    let tmp = v[i1];
-    v[i1] = v[i2];
-    v[i2] = tmp;
-}
-
-fn swap(v: &mut Vec<u128>, i1: usize, i2: usize, asc: bool) {
-    if v[i1] > v[i2] && asc {
-        let tmp = v[i1];
-        v[i1] = v[i2];
-        v[i2] = tmp;
-    }
-    if v[i1] < v[i2] && !asc {
-        let tmp = v[i1];
-        v[i1] = v[i2];
-        v[i2] = tmp;
+fn swap<T>(v: &mut [T], i1: usize, i2: usize, order: &Order<T>, observer: &mut dyn SortObserver) {
+    if order.compare(&v[i1], &v[i2]) == Ordering::Greater {
+        observer.observe(SortEvent::Swap(i1, i2));
+        v.swap(i1, i2);
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{quicksort, quicksort_recursive, Hoare, Lomuto};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::{
+        quicksort, quicksort_by, quicksort_by_key, quicksort_in_place, quicksort_in_place_by,
+        quicksort_observed, quicksort_parallel, quicksort_recursive, quicksort_recursive_observed,
+        select_nth, Direction, Hoare, Introsort, Lomuto, MedianOfMedians, Order, Partition, Random,
+        SortEvent, ThreeWay, WithPivot,
+    };
 
     #[test]
     fn test_quicksort_hoare() -> Result<(), Box<dyn std::error::Error>> {
         let cases = gen_cases();
         for case in cases {
-            let keep = case.clone();
-            let res = quicksort(case.0, case.2, Box::new(Hoare {}));
+            let mut res = case.0.clone();
+            quicksort(&mut res, &case.2.into(), Box::new(Hoare {}));
             if res != case.1 {
-                return Err(format!("{:?} -> {:?}", keep, res).to_string().into());
+                return Err(format!("{:?} -> {:?}", case, res).to_string().into());
             }
         }
         Ok(())
@@ -218,21 +378,39 @@ mod tests {
     fn test_quicksort_lomuto() -> Result<(), Box<dyn std::error::Error>> {
         let cases = gen_cases();
         for case in cases {
-            let keep = case.clone();
-            let res = quicksort(case.0, case.2, Box::new(Lomuto {}));
+            let mut res = case.0.clone();
+            quicksort(&mut res, &case.2.into(), Box::new(Lomuto {}));
             if res != case.1 {
-                return Err(format!("{:?} -> {:?}", keep, res).to_string().into());
+                return Err(format!("{:?} -> {:?}", case, res).to_string().into());
             }
         }
         Ok(())
     }
 
+    #[test]
+    fn test_quicksort_three_way() -> Result<(), Box<dyn std::error::Error>> {
+        let cases = gen_cases();
+        for case in cases {
+            let mut res = case.0.clone();
+            quicksort(&mut res, &case.2.into(), Box::new(ThreeWay {}));
+            if res != case.1 {
+                return Err(format!("{:?} -> {:?}", case, res).to_string().into());
+            }
+        }
+
+        // Only a few distinct keys: every partition removes the whole block of one key.
+        let input: Vec<u128> = (0..3000).map(|i| (i * 7919 % 5) as u128).collect();
+        assert!(count_compares(&input, Box::new(ThreeWay {})) <= 5 * input.len());
+        assert!(count_compares(&input, Box::new(Lomuto {})) > input.len() * input.len() / 10);
+        Ok(())
+    }
+
     #[test]
     fn test_quicksort_recursive() -> Result<(), Box<dyn std::error::Error>> {
         let cases = gen_cases();
         for case in cases {
             let keep = case.clone();
-            let res = quicksort_recursive(case.0, case.2);
+            let res = quicksort_recursive(case.0, &case.2.into());
             if res != case.1 {
                 return Err(format!("{:?} -> {:?}", keep, res).to_string().into());
             }
@@ -240,11 +418,279 @@ mod tests {
         Ok(())
     }
 
-    fn gen_cases() -> Vec<(Vec<u128>, Vec<u128>, bool)> {
+    #[test]
+    fn test_quicksort_recursive_observed() {
+        let count = |v: Vec<u128>| -> (usize, usize) {
+            let (mut swaps, mut moves) = (0, 0);
+            let mut expected = v.clone();
+            expected.sort();
+            let res = quicksort_recursive_observed(v, &Order::ascending(), &mut |e| match e {
+                SortEvent::Swap(_, _) => swaps += 1,
+                SortEvent::Move => moves += 1,
+                e => panic!("unexpected event {:?}", e),
+            });
+            assert_eq!(res, expected);
+            (swaps, moves)
+        };
+        assert_eq!(count(vec![]), (0, 0));
+        assert_eq!(count(vec![2, 1]), (1, 0));
+        assert_eq!(count(vec![1, 2]), (0, 0));
+        // Equal elements are moved aside once and aren't sorted again.
+        assert_eq!(count(vec![7; 100]), (0, 99));
+        // Every element but pivot is moved on every level: 6 into two sub-arrays of 3,
+        // then 2 out of each; swap_remove leaves [6, 5], which is swapped.
+        assert_eq!(count(vec![0, 1, 2, 3, 4, 5, 6]), (1, 6 + 2 + 2));
+    }
+
+    #[test]
+    fn test_quicksort_generic() {
+        let mut words = vec!["pear", "apple", "fig", "banana", "cherry"];
+        quicksort(&mut words, &Order::ascending(), Box::new(Hoare {}));
+        assert_eq!(words, vec!["apple", "banana", "cherry", "fig", "pear"]);
+
+        let mut floats = vec![2.5, -1.0, 3.25, 0.0, -7.5];
+        quicksort_by(&mut floats, |a: &f64, b| b.partial_cmp(a).unwrap(), Box::new(Lomuto {}));
+        assert_eq!(floats, vec![3.25, 2.5, 0.0, -1.0, -7.5]);
+
+        #[derive(Debug, PartialEq)]
+        struct User {
+            name: &'static str,
+            age: u8,
+        }
+        let mut users = vec![
+            User {
+                name: "bob",
+                age: 42,
+            },
+            User {
+                name: "alice",
+                age: 7,
+            },
+            User {
+                name: "eve",
+                age: 19,
+            },
+        ];
+        quicksort_by_key(&mut users, |u| u.age, Box::new(Hoare {}));
+        assert_eq!(users.iter().map(|u| u.name).collect::<Vec<_>>(), vec!["alice", "eve", "bob"]);
+        quicksort_by_key(&mut users, |u| u.name, Box::new(Lomuto {}));
+        assert_eq!(users.iter().map(|u| u.age).collect::<Vec<_>>(), vec![7, 42, 19]);
+
+        let res = quicksort_recursive(vec!["b", "c", "a"], &Order::descending());
+        assert_eq!(res, vec!["c", "b", "a"]);
+        let res = quicksort_recursive(vec![3, 1, 3, 3, 2, 3, 1], &Order::descending());
+        assert_eq!(res, vec![3, 3, 3, 3, 2, 1, 1]);
+    }
+
+    #[test]
+    fn test_quicksort_multi_key() {
+        type Row = (&'static str, u32);
+        let mut rows: Vec<Row> = vec![
+            ("open", 20240102),
+            ("closed", 20240101),
+            ("open", 20240305),
+            ("closed", 20240410),
+            ("open", 20240101),
+        ];
+        // Status asc, date desc.
+        let order = Order::by_key(|r: &Row| r.0).then(Order::by_key(|r: &Row| r.1).reverse());
+        let expected = vec![
+            ("closed", 20240410),
+            ("closed", 20240101),
+            ("open", 20240305),
+            ("open", 20240102),
+            ("open", 20240101),
+        ];
+        let mut res = rows.clone();
+        quicksort(&mut res, &order, Box::new(ThreeWay {}));
+        assert_eq!(res, expected);
+        quicksort_in_place(&mut rows, &order, Box::new(Introsort::new(Hoare {})));
+        assert_eq!(rows, expected);
+    }
+
+    #[test]
+    fn test_quicksort_observed() {
+        for case in gen_cases() {
+            let partitions: Vec<Box<dyn Partition<u128>>> = vec![
+                Box::new(Hoare {}),
+                Box::new(Lomuto {}),
+                Box::new(ThreeWay {}),
+            ];
+            for partition in partitions {
+                let mut events: Vec<SortEvent> = Vec::new();
+                let mut res = case.0.clone();
+                let cmp = |a: &u128, b: &u128| case.2.apply(a.cmp(b));
+                quicksort_observed(&mut res, cmp, partition, &mut |e| events.push(e));
+                assert_eq!(res, case.1);
+
+                // Replaying swaps on the input gives the same sorted result.
+                let mut replay = case.0.clone();
+                for event in &events {
+                    match *event {
+                        SortEvent::Compare(i, j) => assert!(i < res.len() && j < res.len()),
+                        SortEvent::Swap(i, j) => replay.swap(i, j),
+                        SortEvent::Partition {
+                            start,
+                            stop,
+                            pivot_range: (lt, gt),
+                        } => assert!(start <= lt && lt <= gt + 1 && gt <= stop),
+                        SortEvent::Move => panic!("in place sort moved element out"),
+                    }
+                }
+                assert_eq!(replay, case.1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_quicksort_fuzz() -> Result<(), Box<dyn std::error::Error>> {
+        // Every case has its own seed, so a failed one can be rerun alone.
+        for seed in 0..2000 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let len: usize = rng.gen_range(0..64);
+            // Few distinct values give many duplicates, extremes check there are no overflows.
+            let values: [u128; 5] = [0, 1, rng.gen(), u128::MAX - 1, u128::MAX];
+            let input: Vec<u128> =
+                (0..len).map(|_| values[rng.gen_range(0..values.len())]).collect();
+            let direction = if rng.gen() {
+                Direction::Ascending
+            } else {
+                Direction::Descending
+            };
+            let order: Order<u128> = direction.into();
+            let mut expected = input.clone();
+            expected.sort_by(|a, b| direction.apply(a.cmp(b)));
+
+            let partitions: Vec<Box<dyn Partition<u128>>> = vec![
+                Box::new(Hoare {}),
+                Box::new(Lomuto {}),
+                Box::new(ThreeWay {}),
+                Box::new(Introsort::new(Hoare {}).with_insertion_threshold(4)),
+                Box::new(WithPivot::new(Lomuto {}, Random::new(rng.gen()))),
+                Box::new(WithPivot::new(ThreeWay {}, MedianOfMedians {})),
+            ];
+            for partition in partitions {
+                let mut res = input.clone();
+                quicksort(&mut res, &order, partition);
+                if res != expected {
+                    return Err(
+                        format!("seed {}: {:?} {:?} -> {:?}", seed, input, direction, res).into()
+                    );
+                }
+            }
+            let mut res = input.clone();
+            quicksort_parallel(&mut res, &order, Box::new(Hoare {}), 4);
+            if res != expected {
+                return Err(format!(
+                    "seed {} parallel: {:?} {:?} -> {:?}",
+                    seed, input, direction, res
+                )
+                .into());
+            }
+            let mut res = input.clone();
+            quicksort_in_place(&mut res, &order, Box::new(Introsort::new(Lomuto {})));
+            if res != expected {
+                return Err(format!(
+                    "seed {} in place: {:?} {:?} -> {:?}",
+                    seed, input, direction, res
+                )
+                .into());
+            }
+            let res = quicksort_recursive(input.clone(), &order);
+            if res != expected {
+                return Err(format!(
+                    "seed {} recursive: {:?} {:?} -> {:?}",
+                    seed, input, direction, res
+                )
+                .into());
+            }
+            if len > 0 {
+                let k = rng.gen_range(0..len);
+                let mut res = input.clone();
+                if select_nth(&mut res, k, &order, Box::new(Hoare {})) != Some(&expected[k]) {
+                    return Err(format!(
+                        "seed {} select {}: {:?} {:?} -> {:?}",
+                        seed, k, input, direction, res
+                    )
+                    .into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_quicksort_in_place() -> Result<(), Box<dyn std::error::Error>> {
+        for case in gen_cases() {
+            let partitions: Vec<Box<dyn Partition<u128>>> = vec![
+                Box::new(Hoare {}),
+                Box::new(Lomuto {}),
+                Box::new(ThreeWay {}),
+                Box::new(Introsort::new(Lomuto {}).with_insertion_threshold(2)),
+            ];
+            for partition in partitions {
+                let mut res = case.0.clone();
+                quicksort_in_place(&mut res, &case.2.into(), partition);
+                if res != case.1 {
+                    return Err(format!("{:?} -> {:?}", case, res).into());
+                }
+            }
+        }
+
+        // Records are compared by key only, so equal keys show that elements are moved
+        // exactly as by iterative quicksort.
+        type Record = (u128, usize);
+        type PartitionFactory = fn() -> Box<dyn Partition<Record>>;
+        let input: Vec<Record> = (0..5000).map(|i| ((i * 7919 % 101) as u128, i)).collect();
+        let partitions: Vec<PartitionFactory> =
+            vec![|| Box::new(Hoare {}), || Box::new(Lomuto {}), || {
+                Box::new(ThreeWay {})
+            }];
+        for partition in partitions {
+            let mut expected = input.clone();
+            quicksort_by_key(&mut expected, |r| r.0, partition());
+            let mut res = input.clone();
+            quicksort_in_place_by(&mut res, |a, b| a.0.cmp(&b.0), partition());
+            if res != expected {
+                return Err("in place result differs from iterative one".into());
+            }
+        }
+        Ok(())
+    }
+
+    // Sort copy of input ascending and count comparisons made by partition.
+    pub(crate) fn count_compares(input: &[u128], partition: Box<dyn Partition<u128>>) -> usize {
+        let mut compares: usize = 0;
+        let mut res = input.to_vec();
+        quicksort_observed(&mut res, u128::cmp, partition, &mut |e| {
+            if let SortEvent::Compare(_, _) = e {
+                compares += 1
+            }
+        });
+        assert!(res.windows(2).all(|w| w[0] <= w[1]));
+        compares
+    }
+
+    pub(crate) fn gen_cases() -> Vec<(Vec<u128>, Vec<u128>, Direction)> {
         vec![
-            (vec![4, 2, 0, 9, 2], vec![0, 2, 2, 4, 9], true),
-            (vec![55, 8, 12, 34, 5, 7, 122, 34, 0], vec![0, 5, 7, 8, 12, 34, 34, 55, 122], true),
-            (vec![55, 8, 12, 5, 7, 122, 34, 0], vec![122, 55, 34, 12, 8, 7, 5, 0], false),
+            (vec![4, 2, 0, 9, 2], vec![0, 2, 2, 4, 9], Direction::Ascending),
+            (
+                vec![55, 8, 12, 34, 5, 7, 122, 34, 0],
+                vec![0, 5, 7, 8, 12, 34, 34, 55, 122],
+                Direction::Ascending,
+            ),
+            (
+                vec![55, 8, 12, 5, 7, 122, 34, 0],
+                vec![122, 55, 34, 12, 8, 7, 5, 0],
+                Direction::Descending,
+            ),
+            (vec![], vec![], Direction::Ascending),
+            (vec![7], vec![7], Direction::Descending),
+            (
+                vec![u128::MAX, 0, u128::MAX, 1],
+                vec![0, 1, u128::MAX, u128::MAX],
+                Direction::Ascending,
+            ),
         ]
     }
 }
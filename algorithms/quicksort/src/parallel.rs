@@ -0,0 +1,128 @@
+use std::cmp::Ordering;
+
+use crate::{sort_range, Order, Partition, PivotRange, SortContext};
+
+// Ranges not longer than this are sorted by sequential loop on current thread.
+pub const PARALLEL_CUTOFF: usize = 4096;
+
+pub fn quicksort_parallel<T>(
+    v: &mut [T],
+    order: &Order<T>,
+    partition: Box<dyn Partition<T> + Sync>,
+    cutoff: usize,
+) where
+    T: Send,
+{
+    quicksort_parallel_by(v, |a, b| order.compare(a, b), partition, cutoff)
+}
+
+// Same as quicksort_by but subranges longer than cutoff are sorted on rayon thread pool.
+// Forked subranges see only their own part of the slice, so Introsort counts its depth limit
+// from the subrange length and can switch to heapsort at other points than sequential run.
+// Partition is shared by threads, so it has to be Sync: Random pivot keeps its state
+// in Cell and can't be used here.
+pub fn quicksort_parallel_by<T, F>(
+    v: &mut [T],
+    cmp: F,
+    partition: Box<dyn Partition<T> + Sync>,
+    cutoff: usize,
+) where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    if v.len() < 2 {
+        return;
+    }
+    fork(v, 0, &cmp, partition.as_ref(), cutoff.max(1))
+}
+
+fn fork<T, F>(
+    v: &mut [T],
+    depth: usize,
+    cmp: &F,
+    partition: &(dyn Partition<T> + Sync),
+    cutoff: usize,
+) where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    let mut cmp_mut = |a: &T, b: &T| cmp(a, b);
+    let mut observer = |_| {};
+    let mut ctx = SortContext::new(&mut cmp_mut, &mut observer);
+    let ss = (0, v.len() - 1);
+    if v.len() <= cutoff {
+        return sort_range(v, ss, depth, partition, &mut ctx);
+    }
+    if partition.do_finish(v, ss, depth, &mut ctx) {
+        return;
+    }
+    let pivot_range: PivotRange = partition.do_part(v, ss.0, ss.1, &mut ctx);
+    let mut sub_ranges: Vec<(usize, usize)> = Vec::with_capacity(2);
+    partition.do_stack(&mut sub_ranges, ss, pivot_range);
+    sub_ranges.sort();
+
+    // Sub ranges don't overlap, so slice is split into independent parts for every of them.
+    rayon::scope(|s| {
+        let mut rest: &mut [T] = v;
+        let mut offset: usize = 0;
+        for (start, stop) in sub_ranges {
+            let (_, tail) = std::mem::take(&mut rest).split_at_mut(start - offset);
+            let (range, tail) = tail.split_at_mut(stop - start + 1);
+            rest = tail;
+            offset = stop + 1;
+            s.spawn(move |_| fork(range, depth + 1, cmp, partition, cutoff));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parallel::*;
+    use crate::{quicksort, quicksort_by_key, Hoare, Lomuto, ThreeWay};
+
+    type Record = (u128, usize);
+    // The same partition scheme for sequential and parallel runs.
+    type PartitionPair = (Box<dyn Partition<Record>>, Box<dyn Partition<Record> + Sync>);
+
+    #[test]
+    fn test_quicksort_parallel() {
+        for (input, expected, direction) in crate::tests::gen_cases() {
+            let mut res = input.clone();
+            quicksort_parallel(&mut res, &direction.into(), Box::new(Hoare {}), 2);
+            assert_eq!(res, expected);
+            let mut res = input.clone();
+            quicksort_parallel(&mut res, &direction.into(), Box::new(Lomuto {}), 2);
+            assert_eq!(res, expected);
+            let mut res = input.clone();
+            quicksort_parallel(&mut res, &direction.into(), Box::new(ThreeWay {}), 2);
+            assert_eq!(res, expected);
+        }
+
+        let input: Vec<u128> = (0..100_000).map(|i| (i * 7919 % 100_003) as u128).collect();
+        let mut expected = input.clone();
+        quicksort(&mut expected, &Order::descending(), Box::new(Hoare {}));
+        let mut res = input.clone();
+        quicksort_parallel(&mut res, &Order::descending(), Box::new(Hoare {}), PARALLEL_CUTOFF);
+        assert_eq!(res, expected);
+    }
+
+    #[test]
+    fn test_quicksort_parallel_identical() {
+        // Records are compared by key only, so equal keys show how partitions moved them.
+        // Partitions without depth limit or state split every range the same way in both runs,
+        // so even equal keys end up in the same order; that doesn't hold for Introsort.
+        let input: Vec<Record> = (0..50_000).map(|i| ((i * 7919 % 101) as u128, i)).collect();
+        let cases: Vec<PartitionPair> = vec![
+            (Box::new(Hoare {}), Box::new(Hoare {})),
+            (Box::new(Lomuto {}), Box::new(Lomuto {})),
+            (Box::new(ThreeWay {}), Box::new(ThreeWay {})),
+        ];
+        for (sequential, parallel) in cases {
+            let mut expected = input.clone();
+            quicksort_by_key(&mut expected, |r| r.0, sequential);
+            let mut res = input.clone();
+            quicksort_parallel_by(&mut res, |a, b| a.0.cmp(&b.0), parallel, 64);
+            assert_eq!(res, expected);
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use std::cell::Cell;
+use std::cmp::Ordering;
+
+use crate::{Partition, PivotRange, SortContext};
+
+// Ranges shorter than this use median of three instead of ninther.
+const NINTHER_THRESHOLD: usize = 40;
+
+// Strategy to choose pivot for [start, stop] range.
+pub trait Pivot<T> {
+    fn choose(&self, v: &[T], start: usize, stop: usize, ctx: &mut SortContext<T>) -> usize;
+}
+
+impl<T, S> Pivot<T> for Box<S>
+where
+    S: Pivot<T> + ?Sized,
+{
+    fn choose(&self, v: &[T], start: usize, stop: usize, ctx: &mut SortContext<T>) -> usize {
+        self.as_ref().choose(v, start, stop, ctx)
+    }
+}
+
+pub struct First {}
+
+impl<T> Pivot<T> for First {
+    fn choose(&self, _v: &[T], start: usize, _stop: usize, _ctx: &mut SortContext<T>) -> usize {
+        start
+    }
+}
+
+pub struct Last {}
+
+impl<T> Pivot<T> for Last {
+    fn choose(&self, _v: &[T], _start: usize, stop: usize, _ctx: &mut SortContext<T>) -> usize {
+        stop
+    }
+}
+
+pub struct Middle {}
+
+impl<T> Pivot<T> for Middle {
+    fn choose(&self, _v: &[T], start: usize, stop: usize, _ctx: &mut SortContext<T>) -> usize {
+        start + (stop - start) / 2
+    }
+}
+
+// Uniformly random pivot; the same seed gives the same sequence of pivots.
+pub struct Random {
+    state: Cell<u64>,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        // Xorshift state can't be zero.
+        Self {
+            state: Cell::new(seed.max(1)),
+        }
+    }
+
+    // Xorshift64*.
+    fn next(&self) -> u64 {
+        let mut x = self.state.get();
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state.set(x);
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+}
+
+impl<T> Pivot<T> for Random {
+    fn choose(&self, _v: &[T], start: usize, stop: usize, _ctx: &mut SortContext<T>) -> usize {
+        start + (self.next() % (stop - start + 1) as u64) as usize
+    }
+}
+
+// Median of the first, the middle and the last elements.
+pub struct MedianOfThree {}
+
+impl<T> Pivot<T> for MedianOfThree {
+    fn choose(&self, v: &[T], start: usize, stop: usize, ctx: &mut SortContext<T>) -> usize {
+        median_of_three(v, start, start + (stop - start) / 2, stop, ctx)
+    }
+}
+
+// Tukey's ninther: median of three medians of three for long ranges.
+pub struct Ninther {}
+
+impl<T> Pivot<T> for Ninther {
+    fn choose(&self, v: &[T], start: usize, stop: usize, ctx: &mut SortContext<T>) -> usize {
+        let len = stop - start + 1;
+        if len < NINTHER_THRESHOLD {
+            return MedianOfThree {}.choose(v, start, stop, ctx);
+        }
+        let step = len / 8;
+        let mid = start + len / 2;
+        let a = median_of_three(v, start, start + step, start + 2 * step, ctx);
+        let b = median_of_three(v, mid - step, mid, mid + step, ctx);
+        let c = median_of_three(v, stop - 2 * step, stop - step, stop, ctx);
+        median_of_three(v, a, b, c, ctx)
+    }
+}
+
+// Exact median of medians of groups of five, found in linear time.
+// With ThreeWay partition it guarantees linear selection and n log n sorting even on duplicates.
+pub struct MedianOfMedians {}
+
+impl<T> Pivot<T> for MedianOfMedians {
+    fn choose(&self, v: &[T], start: usize, stop: usize, ctx: &mut SortContext<T>) -> usize {
+        let mut indices: Vec<usize> = (start..=stop).collect();
+        median_of_medians(v, &mut indices, ctx)
+    }
+}
+
+// Indices are reordered inside groups of five, values in v aren't moved.
+fn median_of_medians<T>(v: &[T], indices: &mut [usize], ctx: &mut SortContext<T>) -> usize {
+    let mut medians: Vec<usize> = indices
+        .chunks_mut(5)
+        .map(|group| {
+            sort_indices(v, group, ctx);
+            group[(group.len() - 1) / 2]
+        })
+        .collect();
+    let k = (medians.len() - 1) / 2;
+    select_index(v, &mut medians, k, ctx)
+}
+
+// Index of the element which would be k-th if indices were sorted by their values.
+fn select_index<T>(v: &[T], indices: &mut [usize], k: usize, ctx: &mut SortContext<T>) -> usize {
+    if indices.len() <= 5 {
+        sort_indices(v, indices, ctx);
+        return indices[k];
+    }
+    let pivot = median_of_medians(v, indices, ctx);
+    let mut smaller: Vec<usize> = Vec::new();
+    let mut bigger: Vec<usize> = Vec::new();
+    for &i in indices.iter() {
+        match ctx.compare(v, i, pivot) {
+            Ordering::Less => smaller.push(i),
+            Ordering::Greater => bigger.push(i),
+            Ordering::Equal => (),
+        }
+    }
+    let equal = indices.len() - smaller.len() - bigger.len();
+    if k < smaller.len() {
+        select_index(v, &mut smaller, k, ctx)
+    } else if k < smaller.len() + equal {
+        pivot
+    } else {
+        let k = k - smaller.len() - equal;
+        select_index(v, &mut bigger, k, ctx)
+    }
+}
+
+// Insertion sort of short group of indices by their values.
+fn sort_indices<T>(v: &[T], indices: &mut [usize], ctx: &mut SortContext<T>) {
+    for i in 1..indices.len() {
+        let mut j = i;
+        while j > 0 && ctx.compare(v, indices[j - 1], indices[j]) == Ordering::Greater {
+            indices.swap(j - 1, j);
+            j -= 1;
+        }
+    }
+}
+
+fn median_of_three<T>(v: &[T], a: usize, b: usize, c: usize, ctx: &mut SortContext<T>) -> usize {
+    let ab = ctx.compare(v, a, b) == Ordering::Less;
+    let bc = ctx.compare(v, b, c) == Ordering::Less;
+    if ab == bc {
+        return b;
+    }
+    let ac = ctx.compare(v, a, c) == Ordering::Less;
+    if ab == ac {
+        c
+    } else {
+        a
+    }
+}
+
+// Partition scheme with custom pivot strategy.
+// Chosen pivot is swapped to the place where partition scheme expects it.
+pub struct WithPivot<P, S> {
+    partition: P,
+    pivot: S,
+}
+
+impl<P, S> WithPivot<P, S> {
+    pub fn new(partition: P, pivot: S) -> Self {
+        Self { partition, pivot }
+    }
+}
+
+impl<T, P, S> Partition<T> for WithPivot<P, S>
+where
+    P: Partition<T>,
+    S: Pivot<T>,
+{
+    fn do_part(
+        &self,
+        v: &mut [T],
+        start: usize,
+        stop: usize,
+        ctx: &mut SortContext<T>,
+    ) -> PivotRange {
+        let chosen = self.pivot.choose(v, start, stop, ctx);
+        let expected = self.partition.pivot_index(start, stop);
+        if chosen != expected {
+            ctx.swap(v, chosen, expected);
+        }
+        self.partition.do_part(v, start, stop, ctx)
+    }
+
+    fn do_stack(
+        &self,
+        stack: &mut Vec<(usize, usize)>,
+        ss: (usize, usize),
+        pivot_range: PivotRange,
+    ) {
+        self.partition.do_stack(stack, ss, pivot_range)
+    }
+
+    fn pivot_index(&self, start: usize, stop: usize) -> usize {
+        self.partition.pivot_index(start, stop)
+    }
+
+    fn do_finish(
+        &self,
+        v: &mut [T],
+        ss: (usize, usize),
+        depth: usize,
+        ctx: &mut SortContext<T>,
+    ) -> bool {
+        self.partition.do_finish(v, ss, depth, ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pivot::*;
+    use crate::tests::count_compares;
+    use crate::{quicksort, Hoare, Introsort, Lomuto, Order, ThreeWay};
+
+    type PivotFactory = fn() -> Box<dyn Pivot<u128>>;
+
+    #[test]
+    fn test_pivot_choose() {
+        let mut cmp = u128::cmp;
+        let mut observer = |_| {};
+        let mut ctx = SortContext::new(&mut cmp, &mut observer);
+        let v: Vec<u128> = vec![5, 9, 1, 7, 3];
+        assert_eq!(First {}.choose(&v, 0, 4, &mut ctx), 0);
+        assert_eq!(Last {}.choose(&v, 0, 4, &mut ctx), 4);
+        assert_eq!(Middle {}.choose(&v, 1, 4, &mut ctx), 2);
+        // Median of 5, 1 and 3.
+        assert_eq!(MedianOfThree {}.choose(&v, 0, 4, &mut ctx), 4);
+        assert_eq!(Ninther {}.choose(&v, 0, 4, &mut ctx), 4);
+
+        // Ninther of 0..100 shuffled in blocks is close to the real median.
+        let v: Vec<u128> = (0..100).map(|i| (i * 37) % 100).collect();
+        let ninther = v[Ninther {}.choose(&v, 0, 99, &mut ctx)];
+        assert!((25..75).contains(&ninther));
+        // Median of medians is guaranteed to be between 30th and 70th percentiles.
+        let median = v[MedianOfMedians {}.choose(&v, 0, 99, &mut ctx)];
+        assert!((30..70).contains(&median));
+
+        let random = Random::new(42);
+        let chosen: Vec<usize> = (0..10).map(|_| random.choose(&v, 10, 20, &mut ctx)).collect();
+        assert!(chosen.iter().all(|i| (10..=20).contains(i)));
+        let random = Random::new(42);
+        assert_eq!(
+            chosen,
+            (0..10).map(|_| random.choose(&v, 10, 20, &mut ctx)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_with_pivot() {
+        let input: Vec<u128> = (0..500).map(|i| (i * 7919 % 1009) as u128).collect();
+        let mut expected = input.clone();
+        expected.sort();
+        let pivots: Vec<PivotFactory> = vec![
+            || Box::new(First {}),
+            || Box::new(Last {}),
+            || Box::new(Middle {}),
+            || Box::new(Random::new(7)),
+            || Box::new(MedianOfThree {}),
+            || Box::new(Ninther {}),
+            || Box::new(MedianOfMedians {}),
+        ];
+        for pivot in pivots {
+            let mut res = input.clone();
+            quicksort(&mut res, &Order::ascending(), Box::new(WithPivot::new(Hoare {}, pivot())));
+            assert_eq!(res, expected);
+            let mut res = input.clone();
+            quicksort(&mut res, &Order::ascending(), Box::new(WithPivot::new(Lomuto {}, pivot())));
+            assert_eq!(res, expected);
+            let mut res = input.clone();
+            quicksort(
+                &mut res,
+                &Order::ascending(),
+                Box::new(WithPivot::new(ThreeWay {}, pivot())),
+            );
+            assert_eq!(res, expected);
+            let mut res = input.clone();
+            let partition = Introsort::new(WithPivot::new(Lomuto {}, pivot()));
+            quicksort(&mut res, &Order::ascending(), Box::new(partition));
+            assert_eq!(res, expected);
+        }
+    }
+
+    #[test]
+    fn test_with_pivot_sorted() {
+        // On already sorted input median of three avoids quadratic Lomuto.
+        let input: Vec<u128> = (0..1000).collect();
+        let last = count_compares(&input, Box::new(WithPivot::new(Lomuto {}, Last {})));
+        let median = count_compares(&input, Box::new(WithPivot::new(Lomuto {}, MedianOfThree {})));
+        assert!(median * 10 < last);
+    }
+}
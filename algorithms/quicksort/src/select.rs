@@ -0,0 +1,197 @@
+use std::cmp::Ordering;
+
+use crate::{Order, Partition, PivotRange, SortContext};
+
+// Reorder v so that element at k is the one which would be there after sorting,
+// elements before it aren't bigger and elements after it aren't smaller.
+// Only the side of each partition containing k is partitioned again.
+// Returns None if k is out of bounds.
+pub fn select_nth<'a, T>(
+    v: &'a mut [T],
+    k: usize,
+    order: &Order<T>,
+    partition: Box<dyn Partition<T>>,
+) -> Option<&'a T> {
+    select_nth_by(v, k, |a, b| order.compare(a, b), partition)
+}
+
+pub fn select_nth_by<T, F>(
+    v: &mut [T],
+    k: usize,
+    mut cmp: F,
+    partition: Box<dyn Partition<T>>,
+) -> Option<&T>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if k >= v.len() {
+        return None;
+    }
+    let mut observer = |_| {};
+    let mut ctx = SortContext::new(&mut cmp, &mut observer);
+    let (mut start, mut stop) = (0, v.len() - 1);
+    while start < stop {
+        let (lt, gt): PivotRange = partition.do_part(v, start, stop, &mut ctx);
+        if k < lt {
+            stop = lt - 1;
+        } else if k > gt {
+            start = gt + 1;
+        } else {
+            break;
+        }
+    }
+    Some(&v[k])
+}
+
+// Lower median, None for empty slice.
+pub fn median<T>(v: &mut [T], partition: Box<dyn Partition<T>>) -> Option<&T>
+where
+    T: Ord,
+{
+    let k = v.len().checked_sub(1)? / 2;
+    select_nth(v, k, &Order::ascending(), partition)
+}
+
+// Move the first k elements by order to the beginning and return them.
+// Returned elements aren't sorted; whole slice is returned if k isn't less than its length.
+pub fn top_k<'a, T>(
+    v: &'a mut [T],
+    k: usize,
+    order: &Order<T>,
+    partition: Box<dyn Partition<T>>,
+) -> &'a mut [T] {
+    let k = k.min(v.len());
+    if k > 0 && k < v.len() {
+        select_nth(v, k - 1, order, partition);
+    }
+    &mut v[..k]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::select::*;
+    use crate::tests::count_compares;
+    use crate::{Hoare, Last, Lomuto, MedianOfMedians, ThreeWay, WithPivot};
+
+    type PartitionFactory = fn() -> Box<dyn Partition<u128>>;
+
+    fn gen_partitions() -> Vec<PartitionFactory> {
+        vec![
+            || Box::new(Hoare {}),
+            || Box::new(Lomuto {}),
+            || Box::new(ThreeWay {}),
+            || Box::new(WithPivot::new(Lomuto {}, MedianOfMedians {})),
+            || Box::new(WithPivot::new(ThreeWay {}, MedianOfMedians {})),
+        ]
+    }
+
+    #[test]
+    fn test_select_nth() {
+        for partition in gen_partitions() {
+            for (input, expected, direction) in crate::tests::gen_cases() {
+                for k in 0..input.len() {
+                    let mut res = input.clone();
+                    assert_eq!(
+                        select_nth(&mut res, k, &direction.into(), partition()),
+                        Some(&expected[k])
+                    );
+                    let ordered =
+                        |a: &u128, b: &u128| direction.apply(a.cmp(b)) != Ordering::Greater;
+                    assert!(res[..k].iter().all(|x| ordered(x, &res[k])));
+                    assert!(res[k + 1..].iter().all(|x| ordered(&res[k], x)));
+                }
+                let mut res = input.clone();
+                assert_eq!(select_nth(&mut res, input.len(), &direction.into(), partition()), None);
+            }
+
+            let mut v: Vec<u128> = vec![];
+            assert_eq!(select_nth(&mut v, 0, &Order::ascending(), partition()), None);
+            assert_eq!(median(&mut v, partition()), None);
+            let mut v: Vec<u128> = vec![7; 100];
+            assert_eq!(select_nth(&mut v, 42, &Order::ascending(), partition()), Some(&7));
+        }
+    }
+
+    #[test]
+    fn test_median_top_k() {
+        for partition in gen_partitions() {
+            let mut v: Vec<u128> = vec![55, 8, 12, 34, 5, 7, 122, 34, 0];
+            assert_eq!(median(&mut v, partition()), Some(&12));
+            let mut v: Vec<u128> = vec![55, 8, 12, 5, 7, 122, 34, 0];
+            assert_eq!(median(&mut v, partition()), Some(&8));
+
+            let mut top = top_k(&mut v, 3, &Order::descending(), partition()).to_vec();
+            top.sort();
+            assert_eq!(top, vec![34, 55, 122]);
+            let mut top = top_k(&mut v, 3, &Order::ascending(), partition()).to_vec();
+            top.sort();
+            assert_eq!(top, vec![0, 5, 7]);
+            assert!(top_k(&mut v, 0, &Order::ascending(), partition()).is_empty());
+            assert_eq!(top_k(&mut v, 100, &Order::ascending(), partition()).len(), 8);
+        }
+    }
+
+    #[test]
+    fn test_select_median_of_medians() {
+        // On sorted input median of medians keeps selection linear while last element is quadratic.
+        let input: Vec<u128> = (0..5000).collect();
+        let mut compares: usize = 0;
+        let mut res = input.clone();
+        let partition = WithPivot::new(ThreeWay {}, MedianOfMedians {});
+        select_nth_by(
+            &mut res,
+            0,
+            |a, b| {
+                compares += 1;
+                a.cmp(b)
+            },
+            Box::new(partition),
+        );
+        assert!(compares < 30 * input.len());
+
+        let last = count_compares(&input, Box::new(WithPivot::new(Lomuto {}, Last {})));
+        assert!(compares * 10 < last);
+    }
+
+    #[test]
+    fn test_select_median_of_medians_adversarial() -> Result<(), Box<dyn std::error::Error>> {
+        let n: u128 = 1000;
+        let inputs: Vec<(&str, Vec<u128>)> = vec![
+            ("equal", vec![7; n as usize]),
+            ("duplicates", (0..n).map(|i| i * 7919 % 3).collect()),
+            ("organ pipe", (0..n).map(|i| i.min(n - 1 - i)).collect()),
+            ("sorted", (0..n).collect()),
+            ("reversed", (0..n).rev().collect()),
+        ];
+        for (name, input) in inputs {
+            let mut expected = input.clone();
+            expected.sort();
+            for k in [0, input.len() / 4, input.len() / 2, input.len() - 1] {
+                for partition in gen_partitions() {
+                    let mut res = input.clone();
+                    let nth = select_nth(&mut res, k, &Order::ascending(), partition()).copied();
+                    // Not only the k-th element, but everything around it is in place.
+                    let partitioned = res[..k].iter().all(|x| *x <= res[k])
+                        && res[k + 1..].iter().all(|x| *x >= res[k]);
+                    if nth != Some(expected[k]) || !partitioned {
+                        return Err(format!("{} k = {}: {:?}", name, k, nth).into());
+                    }
+                }
+
+                // Three-way partition around median of medians stays linear on all of them.
+                let mut compares: usize = 0;
+                let mut res = input.clone();
+                let partition = WithPivot::new(ThreeWay {}, MedianOfMedians {});
+                let cmp = |a: &u128, b: &u128| {
+                    compares += 1;
+                    a.cmp(b)
+                };
+                select_nth_by(&mut res, k, cmp, Box::new(partition));
+                if compares > 30 * input.len() {
+                    return Err(format!("{} k = {}: {} compares", name, k, compares).into());
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,54 +1,310 @@
-type Type = u128;
+use std::cmp::Ordering;
 
-fn selection_sort(mut v: Vec<Type>, asc: bool) -> Vec<Type> {
-    if v.len() == 0 || v.len() == 1 {
-        return v;
+use order::{SortContext, SortObserver};
+
+pub use order::{Direction, Order};
+
+// How the next element is selected on every pass.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Selection {
+    // Scan the rest for the minimum and put it to the front.
+    Linear,
+    // Scan the rest for both the minimum and the maximum and put them to both ends,
+    // so there are half as many passes.
+    DoubleEnded,
+    // Keep the rest as a max heap, so the maximum is selected in O(log n) instead of O(n).
+    // This is heapsort: selection sort with a better data structure for the unsorted part.
+    Heap,
+}
+
+// Not stable: swapping extremum to its place can move other element past equal ones.
+pub fn selection_sort<T>(mut v: Vec<T>, order: &Order<T>) -> Vec<T> {
+    selection_sort_by(&mut v, Selection::Linear, |a, b| order.compare(a, b));
+    v
+}
+
+// Sort slice in place, nothing is copied.
+pub fn selection_sort_by<T, F>(v: &mut [T], selection: Selection, cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    selection_sort_observed(v, selection, cmp, &mut |_| {})
+}
+
+// Same as selection_sort_by, but every compare and swap is reported to observer.
+pub fn selection_sort_observed<T, F>(
+    v: &mut [T],
+    selection: Selection,
+    mut cmp: F,
+    observer: &mut dyn SortObserver,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut ctx = SortContext::new(&mut cmp, observer);
+    match selection {
+        Selection::Linear => linear(v, v.len(), &mut ctx),
+        Selection::DoubleEnded => double_ended(v, &mut ctx),
+        Selection::Heap => heap(v, &mut ctx),
+    }
+}
+
+// Sort only the first k positions, the rest is left in unspecified order.
+// Selection sort stops after k passes, so it's O(n * k).
+pub fn partial_sort<T>(v: &mut [T], k: usize, order: &Order<T>) {
+    partial_sort_by(v, k, |a, b| order.compare(a, b))
+}
+
+pub fn partial_sort_by<T, F>(v: &mut [T], k: usize, mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut observer = |_| {};
+    linear(v, k, &mut SortContext::new(&mut cmp, &mut observer))
+}
+
+// Same as partial_sort but O(n log k): the first k positions are kept as max heap
+// of the smallest elements seen so far, so it's better for large n.
+pub fn heap_partial_sort<T>(v: &mut [T], k: usize, order: &Order<T>) {
+    heap_partial_sort_by(v, k, |a, b| order.compare(a, b))
+}
+
+pub fn heap_partial_sort_by<T, F>(v: &mut [T], k: usize, mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let k = k.min(v.len());
+    if k == 0 {
+        return;
     }
-    for i in 0..v.len() {
-        let extremum_index = find_extremum(v[i..].to_vec(), asc);
-        let tmp_val = v[i];
-        v[i] = v[extremum_index + i];
-        v[extremum_index + i] = tmp_val;
+    let mut observer = |_| {};
+    let mut ctx = SortContext::new(&mut cmp, &mut observer);
+    for root in (0..k / 2).rev() {
+        sift_down(v, 0, root, k, &mut ctx);
+    }
+    // Element smaller than the biggest one in heap replaces it.
+    for i in k..v.len() {
+        if ctx.compare(v, i, 0) == Ordering::Less {
+            ctx.swap(v, 0, i);
+            sift_down(v, 0, 0, k, &mut ctx);
+        }
+    }
+    for end in (1..k).rev() {
+        ctx.swap(v, 0, end);
+        sift_down(v, 0, 0, end, &mut ctx);
     }
-    v
 }
 
-fn find_extremum(v: Vec<Type>, asc: bool) -> usize {
-    let mut tmp_extremum: Type = v[0];
-    let mut tmp_index: usize = 0;
-    for (i, el) in v.iter().enumerate() {
-        if asc && *el < tmp_extremum {
-            tmp_extremum = *el;
-            tmp_index = i
+// Selection sort of the first k positions.
+fn linear<T>(v: &mut [T], k: usize, ctx: &mut SortContext<T>) {
+    for i in 0..k.min(v.len()) {
+        let extremum_index = find_extremum(v, i, v.len(), ctx);
+        ctx.swap(v, i, extremum_index);
+    }
+}
+
+fn double_ended<T>(v: &mut [T], ctx: &mut SortContext<T>) {
+    // Unsorted part is [lo, hi).
+    let (mut lo, mut hi) = (0, v.len());
+    while hi - lo > 1 {
+        let (min, max) = find_min_max(v, lo, hi, ctx);
+        ctx.swap(v, lo, min);
+        // Maximum was at lo and has just been moved to the place of the minimum.
+        let max = if max == lo { min } else { max };
+        ctx.swap(v, hi - 1, max);
+        lo += 1;
+        hi -= 1;
+    }
+}
+
+fn heap<T>(v: &mut [T], ctx: &mut SortContext<T>) {
+    let len = v.len();
+    for root in (0..len / 2).rev() {
+        sift_down(v, 0, root, len, ctx);
+    }
+    // Select the maximum of unsorted part [0, end) and put it right after it.
+    for end in (1..len).rev() {
+        ctx.swap(v, 0, end);
+        sift_down(v, 0, 0, end, ctx);
+    }
+}
+
+// Move element down until it isn't smaller than its children; heap is [start, start + len) range
+// and root is relative to start. Introsort uses it for its heapsort fallback too.
+pub fn sift_down<T>(
+    v: &mut [T],
+    start: usize,
+    mut root: usize,
+    len: usize,
+    ctx: &mut SortContext<T>,
+) {
+    loop {
+        let mut child = 2 * root + 1;
+        if child >= len {
+            return;
+        }
+        if child + 1 < len && ctx.compare(v, start + child, start + child + 1) == Ordering::Less {
+            child += 1;
         }
-        if !asc && *el > tmp_extremum {
-            tmp_extremum = *el;
+        if ctx.compare(v, start + root, start + child) != Ordering::Less {
+            return;
+        }
+        ctx.swap(v, start + root, start + child);
+        root = child;
+    }
+}
+
+// Index of the first minimum in [lo, hi) range.
+fn find_extremum<T>(v: &[T], lo: usize, hi: usize, ctx: &mut SortContext<T>) -> usize {
+    let mut tmp_index: usize = lo;
+    for i in lo + 1..hi {
+        if ctx.compare(v, i, tmp_index) == Ordering::Less {
             tmp_index = i
         }
     }
-    tmp_index as usize
+    tmp_index
+}
+
+// Indices of the first minimum and the last maximum in [lo, hi) range.
+fn find_min_max<T>(v: &[T], lo: usize, hi: usize, ctx: &mut SortContext<T>) -> (usize, usize) {
+    let (mut min, mut max) = (lo, lo);
+    for i in lo + 1..hi {
+        if ctx.compare(v, i, min) == Ordering::Less {
+            min = i
+        } else if ctx.compare(v, i, max) != Ordering::Less {
+            max = i
+        }
+    }
+    (min, max)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{selection_sort, Type};
+    use order::SortEvent;
+
+    use crate::{
+        heap_partial_sort, partial_sort, selection_sort, selection_sort_by,
+        selection_sort_observed, Direction, Order, Selection,
+    };
+
+    type Type = u128;
+    type PartialSorter = fn(&mut [Type], usize, &Order<Type>);
 
     #[test]
     fn it_works() -> Result<(), Box<dyn std::error::Error>> {
-        let cases: Vec<(Vec<Type>, Vec<Type>, bool)> = vec![
-            (vec![], vec![], true),
-            (vec![1], vec![1], false),
-            (vec![5, 1, 6, 2, 7, 10, 2, 4, 4, 10], vec![1, 2, 2, 4, 4, 5, 6, 7, 10, 10], true),
-            (vec![0, 11, 62, 2, 77, 2, 40], vec![77, 62, 40, 11, 2, 2, 0], false),
+        let cases: Vec<(Vec<Type>, Vec<Type>, Direction)> = vec![
+            (vec![], vec![], Direction::Ascending),
+            (vec![1], vec![1], Direction::Descending),
+            (
+                vec![5, 1, 6, 2, 7, 10, 2, 4, 4, 10],
+                vec![1, 2, 2, 4, 4, 5, 6, 7, 10, 10],
+                Direction::Ascending,
+            ),
+            (vec![0, 11, 62, 2, 77, 2, 40], vec![77, 62, 40, 11, 2, 2, 0], Direction::Descending),
         ];
         for case in cases {
-            let sorted = selection_sort(case.0.clone(), case.2);
+            let sorted = selection_sort(case.0.clone(), &case.2.into());
             if sorted != case.1 {
-                return Err(format!("({:?}, {:?}, {}) -> {:?}", case.0, case.1, case.2, sorted)
+                return Err(format!("({:?}, {:?}, {:?}) -> {:?}", case.0, case.1, case.2, sorted)
                     .to_string()
                     .into());
             }
         }
         Ok(())
     }
+
+    #[test]
+    fn test_partial_sort() -> Result<(), Box<dyn std::error::Error>> {
+        let cases: Vec<Vec<Type>> = vec![
+            vec![],
+            vec![1],
+            vec![5, 1, 6, 2, 7, 10, 2, 4, 4, 10],
+            (0..300).map(|i| (i * 7919 % 101) as Type).collect(),
+        ];
+        for case in cases {
+            for direction in [Direction::Ascending, Direction::Descending] {
+                let mut expected = case.clone();
+                expected.sort_by(|a, b| direction.apply(a.cmp(b)));
+                for k in [0, 1, 2, 5, 20, case.len(), case.len() + 1] {
+                    let prefix = &expected[..k.min(case.len())];
+                    let sorters: Vec<PartialSorter> = vec![partial_sort, heap_partial_sort];
+                    for sort in sorters {
+                        let mut res = case.clone();
+                        sort(&mut res, k, &direction.into());
+                        if &res[..prefix.len()] != prefix {
+                            let err = format!("{} {:?}: {:?} -> {:?}", k, direction, case, res);
+                            return Err(err.into());
+                        }
+                        // The rest is still the same elements.
+                        res.sort();
+                        let mut all = case.clone();
+                        all.sort();
+                        if res != all {
+                            return Err(format!("{} {:?}: elements changed", k, direction).into());
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_selection_sort_by() -> Result<(), Box<dyn std::error::Error>> {
+        let mut cases: Vec<Vec<Type>> = vec![
+            vec![],
+            vec![1],
+            vec![2, 1],
+            vec![5, 1, 6, 2, 7, 10, 2, 4, 4, 10],
+            vec![10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+            vec![3, 3, 3, 3],
+        ];
+        cases.push((0..500).map(|i| (i * 7919 % 1009) as Type).collect());
+        for selection in [Selection::Linear, Selection::DoubleEnded, Selection::Heap] {
+            for case in &cases {
+                let mut expected = case.clone();
+                expected.sort();
+                let mut sorted = case.clone();
+                selection_sort_by(&mut sorted, selection, Type::cmp);
+                if sorted != expected {
+                    return Err(format!("{:?}: {:?} -> {:?}", selection, case, sorted).into());
+                }
+                expected.reverse();
+                selection_sort_by(&mut sorted, selection, |a, b| b.cmp(a));
+                if sorted != expected {
+                    return Err(format!("{:?} desc: {:?} -> {:?}", selection, case, sorted).into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_selection_sort_observed() -> Result<(), Box<dyn std::error::Error>> {
+        let input: Vec<Type> = (0..100).map(|i| (i * 7919 % 37) as Type).collect();
+        let mut expected = input.clone();
+        expected.sort();
+        for selection in [Selection::Linear, Selection::DoubleEnded, Selection::Heap] {
+            let mut events: Vec<SortEvent> = Vec::new();
+            let mut sorted = input.clone();
+            selection_sort_observed(&mut sorted, selection, Type::cmp, &mut |e| events.push(e));
+            assert_eq!(sorted, expected);
+
+            // Every swap is reported, so replaying them on the input sorts it too.
+            let mut replay = input.clone();
+            let mut compares: usize = 0;
+            for event in events {
+                match event {
+                    SortEvent::Compare(_, _) => compares += 1,
+                    SortEvent::Swap(i, j) => replay.swap(i, j),
+                    event => return Err(format!("{:?}: {:?}", selection, event).into()),
+                }
+            }
+            assert_eq!(replay, expected);
+            // Linear selection compares every other element of the rest with the minimum.
+            if selection == Selection::Linear {
+                assert_eq!(compares, input.len() * (input.len() - 1) / 2);
+            }
+        }
+        Ok(())
+    }
 }
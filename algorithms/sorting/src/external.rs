@@ -0,0 +1,282 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+
+use order::Direction;
+use quicksort::{quicksort, Hoare, Introsort};
+
+// Keys are stored as little endian u128 one after another.
+const KEY_SIZE: usize = std::mem::size_of::<u128>();
+
+// Runs merged at once by default, so number of open temporary files stays bounded.
+const FAN_IN: usize = 16;
+
+// Sort keys which don't fit into memory: input is read by chunks which fit into memory budget,
+// every chunk is sorted by quicksort and spilled to temporary file as sorted run.
+// Whenever fan_in runs are spilled they are merged into one bigger run,
+// and the remaining runs are merged into output at the end.
+pub struct ExternalSort {
+    // Bytes of keys and IO buffers which can be kept in memory at once.
+    memory_budget: usize,
+    // Number of runs merged at once.
+    fan_in: usize,
+    // Directory for temporary files, system one if None.
+    temp_dir: Option<PathBuf>,
+}
+
+impl ExternalSort {
+    pub fn new(memory_budget: usize) -> Self {
+        Self {
+            memory_budget,
+            fan_in: FAN_IN,
+            temp_dir: None,
+        }
+    }
+
+    pub fn with_temp_dir(mut self, temp_dir: PathBuf) -> Self {
+        self.temp_dir = Some(temp_dir);
+        self
+    }
+
+    // Fan-in is at least 2.
+    pub fn with_fan_in(mut self, fan_in: usize) -> Self {
+        self.fan_in = fan_in.max(2);
+        self
+    }
+
+    // Returns number of sorted runs; input which fits into budget is one run and isn't spilled.
+    // Input and output buffers are open all the time, and spill buffer is open while run is
+    // written; merge into spilled run needs fan_in read buffers more. So budget is split into
+    // fan_in + 3 buffers, but at least one key each, and chunk takes place of all but 3 of them.
+    pub fn sort<R, W>(&self, reader: R, writer: W, direction: Direction) -> io::Result<usize>
+    where
+        R: Read,
+        W: Write,
+    {
+        let buffer_size = self.buffer_size();
+        let chunk_len = (self.memory_budget.saturating_sub(3 * buffer_size) / KEY_SIZE).max(1);
+        let mut reader = BufReader::with_capacity(buffer_size, reader);
+        let mut writer = BufWriter::with_capacity(buffer_size, writer);
+        // Runs of every level: run of level i + 1 is merged from fan_in runs of level i,
+        // so there are fewer than fan_in open runs per level.
+        let mut levels: Vec<Vec<File>> = Vec::new();
+        let mut count: usize = 0;
+        loop {
+            let mut chunk = read_chunk(&mut reader, chunk_len)?;
+            if chunk.is_empty() {
+                break;
+            }
+            quicksort(&mut chunk, &direction.into(), Box::new(Introsort::new(Hoare {})));
+            if count == 0 && chunk.len() < chunk_len {
+                write_keys(&mut writer, &chunk)?;
+                return writer.flush().map(|_| 1);
+            }
+            let mut run = self.spill(|writer| write_keys(writer, &chunk))?;
+            drop(chunk);
+            count += 1;
+            for level in 0.. {
+                if levels.len() == level {
+                    levels.push(Vec::new());
+                }
+                levels[level].push(run);
+                if levels[level].len() < self.fan_in {
+                    break;
+                }
+                let runs = std::mem::take(&mut levels[level]);
+                run = self.spill(|writer| merge_runs(runs, writer, direction, buffer_size))?;
+            }
+        }
+        // Merge the rest by fan_in runs from the smallest ones until they can be merged at once.
+        let mut runs: Vec<File> = levels.into_iter().flatten().collect();
+        while runs.len() > self.fan_in {
+            let merged: Vec<File> = runs.drain(..self.fan_in).collect();
+            runs.push(self.spill(|writer| merge_runs(merged, writer, direction, buffer_size))?);
+        }
+        merge_runs(runs, &mut writer, direction, buffer_size)?;
+        writer.flush()?;
+        Ok(count)
+    }
+
+    fn buffer_size(&self) -> usize {
+        (self.memory_budget / (self.fan_in + 3)).max(KEY_SIZE)
+    }
+
+    // Write run to new temporary file and rewind it for reading.
+    fn spill<F>(&self, write: F) -> io::Result<File>
+    where
+        F: FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+    {
+        let file = match &self.temp_dir {
+            Some(dir) => tempfile::tempfile_in(dir)?,
+            None => tempfile::tempfile()?,
+        };
+        let mut writer = BufWriter::with_capacity(self.buffer_size(), file);
+        write(&mut writer)?;
+        let mut file = writer.into_inner().map_err(|err| err.into_error())?;
+        file.seek(SeekFrom::Start(0))?;
+        Ok(file)
+    }
+}
+
+// K-way merge with heap of current keys of every run.
+fn merge_runs<W>(
+    runs: Vec<File>,
+    writer: &mut W,
+    direction: Direction,
+    buffer_size: usize,
+) -> io::Result<()>
+where
+    W: Write,
+{
+    let mut readers: Vec<BufReader<File>> =
+        runs.into_iter().map(|run| BufReader::with_capacity(buffer_size, run)).collect();
+    // Heap pops the smallest key; for descending order keys are flipped to pop the biggest one.
+    let heap_key = |key: u128| match direction {
+        Direction::Ascending => key,
+        Direction::Descending => u128::MAX - key,
+    };
+    let mut heap: BinaryHeap<Reverse<(u128, usize)>> = BinaryHeap::with_capacity(readers.len());
+    for (i, reader) in readers.iter_mut().enumerate() {
+        if let Some(key) = read_key(reader)? {
+            heap.push(Reverse((heap_key(key), i)));
+        }
+    }
+    while let Some(Reverse((key, i))) = heap.pop() {
+        writer.write_all(&heap_key(key).to_le_bytes())?;
+        if let Some(key) = read_key(&mut readers[i])? {
+            heap.push(Reverse((heap_key(key), i)));
+        }
+    }
+    Ok(())
+}
+
+fn read_chunk<R>(reader: &mut R, chunk_len: usize) -> io::Result<Vec<u128>>
+where
+    R: Read,
+{
+    let mut chunk: Vec<u128> = Vec::with_capacity(chunk_len);
+    while chunk.len() < chunk_len {
+        match read_key(reader)? {
+            Some(key) => chunk.push(key),
+            None => break,
+        }
+    }
+    Ok(chunk)
+}
+
+// None at the end of input, error if input ends in the middle of key.
+fn read_key<R>(reader: &mut R) -> io::Result<Option<u128>>
+where
+    R: Read,
+{
+    let mut buf = [0; KEY_SIZE];
+    let mut read: usize = 0;
+    while read < KEY_SIZE {
+        match reader.read(&mut buf[read..]) {
+            Ok(0) if read == 0 => return Ok(None),
+            Ok(0) => return Err(io::Error::new(ErrorKind::UnexpectedEof, "truncated key")),
+            Ok(n) => read += n,
+            Err(err) if err.kind() == ErrorKind::Interrupted => (),
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(Some(u128::from_le_bytes(buf)))
+}
+
+fn write_keys<W>(writer: &mut W, keys: &[u128]) -> io::Result<()>
+where
+    W: Write,
+{
+    for key in keys {
+        writer.write_all(&key.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use crate::external::*;
+
+    #[test]
+    fn test_external_sort() -> Result<(), Box<dyn std::error::Error>> {
+        let input: Vec<u128> =
+            (0..10_000).map(|i| (i * 7919 % 10_007) as u128 * 0x1_0000_0000_0000_0000).collect();
+        let mut expected = input.clone();
+        expected.sort();
+        let bytes = to_bytes(&input);
+
+        // Budget is shared by fan_in + 3 IO buffers or 3 IO buffers and the chunk, so chunks are
+        // 842, 2526, all, 84 and 40 keys long; the last two need several merge passes.
+        for (memory_budget, fan_in, runs) in [
+            (KEY_SIZE * 1000, FAN_IN, 12),
+            (KEY_SIZE * 3000, FAN_IN, 4),
+            (1 << 20, FAN_IN, 1),
+            (KEY_SIZE * 100 + 1, FAN_IN, 120),
+            (KEY_SIZE * 100, 2, 250),
+        ] {
+            let mut output: Vec<u8> = Vec::new();
+            let count = ExternalSort::new(memory_budget).with_fan_in(fan_in).sort(
+                bytes.as_slice(),
+                &mut output,
+                Direction::Ascending,
+            )?;
+            assert_eq!(count, runs);
+            assert_eq!(from_bytes(&output), expected);
+        }
+
+        let mut output: Vec<u8> = Vec::new();
+        let dir = tempfile::tempdir()?;
+        ExternalSort::new(KEY_SIZE * 999).with_temp_dir(dir.path().to_path_buf()).sort(
+            bytes.as_slice(),
+            &mut output,
+            Direction::Descending,
+        )?;
+        expected.reverse();
+        assert_eq!(from_bytes(&output), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_external_sort_edges() -> Result<(), Box<dyn std::error::Error>> {
+        let mut output: Vec<u8> = Vec::new();
+        assert_eq!(
+            ExternalSort::new(KEY_SIZE).sort(&[][..], &mut output, Direction::Ascending)?,
+            0
+        );
+        assert!(output.is_empty());
+
+        // Chunk of three keys next to one-key input, output and spill buffers is exactly full,
+        // so it's spilled even though the rest of input is empty.
+        let bytes = to_bytes(&[u128::MAX, 0, u128::MAX]);
+        let mut output: Vec<u8> = Vec::new();
+        assert_eq!(
+            ExternalSort::new(KEY_SIZE * 6).sort(
+                bytes.as_slice(),
+                &mut output,
+                Direction::Ascending
+            )?,
+            1
+        );
+        assert_eq!(from_bytes(&output), vec![0, u128::MAX, u128::MAX]);
+
+        let err = ExternalSort::new(KEY_SIZE).sort(
+            &bytes[..KEY_SIZE + 1],
+            &mut Vec::new(),
+            Direction::Ascending,
+        );
+        assert_eq!(err.map_err(|err| err.kind()), Err(ErrorKind::UnexpectedEof));
+        Ok(())
+    }
+
+    fn to_bytes(keys: &[u128]) -> Vec<u8> {
+        keys.iter().flat_map(|key| key.to_le_bytes()).collect()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Vec<u128> {
+        bytes.chunks(KEY_SIZE).map(|key| u128::from_le_bytes(key.try_into().unwrap())).collect()
+    }
+}
@@ -0,0 +1,32 @@
+use std::cmp::Ordering;
+
+use selection_sort::{selection_sort_by, Selection};
+
+// Build max heap in place and move its root to the end one by one.
+// This is selection sort with heap selection, so it's done by selection_sort.
+pub fn heapsort_by<T, F>(v: &mut [T], cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    selection_sort_by(v, Selection::Heap, cmp)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::heap::*;
+
+    #[test]
+    fn test_heapsort() {
+        for n in [0, 1, 2, 3, 7, 8, 9, 100, 1000] {
+            let input: Vec<u128> = (0..n).map(|i| (i * 7919 % 1009) as u128).collect();
+            let mut expected = input.clone();
+            expected.sort();
+            let mut res = input.clone();
+            heapsort_by(&mut res, u128::cmp);
+            assert_eq!(res, expected);
+            expected.reverse();
+            heapsort_by(&mut res, |a, b| b.cmp(a));
+            assert_eq!(res, expected);
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::fmt;
+
+use order::Order;
+use quicksort::{
+    quicksort_observed, quicksort_recursive_observed, Hoare, Lomuto, Partition, SortEvent,
+};
+use selection_sort::{selection_sort_observed, Selection};
+
+// Numbers of operations done by sorting.
+// Rust moves elements by plain memory copy, so swaps and moves are counted from events
+// which sorts report to observer, and only clones are counted by elements themselves.
+#[derive(Debug, Default)]
+pub struct Counters {
+    pub compares: Cell<usize>,
+    pub swaps: Cell<usize>,
+    pub moves: Cell<usize>,
+    pub clones: Cell<usize>,
+}
+
+impl Counters {
+    // Observer for observed sorts which counts their compares, swaps and moves.
+    pub fn observer(&self) -> impl FnMut(SortEvent) + '_ {
+        |event| match event {
+            SortEvent::Compare(_, _) => inc(&self.compares),
+            SortEvent::Swap(_, _) => inc(&self.swaps),
+            SortEvent::Move => inc(&self.moves),
+            SortEvent::Partition { .. } => (),
+        }
+    }
+
+    // Wrap comparator, so every call is counted.
+    pub fn comparator<'a, T, F>(&'a self, mut cmp: F) -> impl FnMut(&T, &T) -> Ordering + 'a
+    where
+        F: FnMut(&T, &T) -> Ordering + 'a,
+    {
+        move |a, b| {
+            inc(&self.compares);
+            cmp(a, b)
+        }
+    }
+
+    // Wrap every element, so algorithms which need Ord and Clone count their operations.
+    pub fn wrap<T>(&self, v: Vec<T>) -> Vec<Counted<'_, T>> {
+        v.into_iter()
+            .map(|value| Counted {
+                value,
+                counters: self,
+            })
+            .collect()
+    }
+}
+
+fn inc(counter: &Cell<usize>) {
+    counter.set(counter.get() + 1)
+}
+
+// Element which counts comparisons and clones of itself.
+#[derive(Debug)]
+pub struct Counted<'a, T> {
+    pub value: T,
+    counters: &'a Counters,
+}
+
+impl<'a, T> Clone for Counted<'a, T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        inc(&self.counters.clones);
+        Self {
+            value: self.value.clone(),
+            counters: self.counters,
+        }
+    }
+}
+
+impl<'a, T> PartialEq for Counted<'a, T>
+where
+    T: Ord,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<'a, T> Eq for Counted<'a, T> where T: Ord {}
+
+impl<'a, T> PartialOrd for Counted<'a, T>
+where
+    T: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a, T> Ord for Counted<'a, T>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        inc(&self.counters.compares);
+        self.value.cmp(&other.value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputKind {
+    Sorted,
+    Reversed,
+    Random,
+    Equal,
+}
+
+impl fmt::Display for InputKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            InputKind::Sorted => "sorted",
+            InputKind::Reversed => "reversed",
+            InputKind::Random => "random",
+            InputKind::Equal => "equal",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Random input is the same for the same length, so reports can be compared.
+pub fn gen_input(kind: InputKind, len: usize) -> Vec<u128> {
+    match kind {
+        InputKind::Sorted => (0..len as u128).collect(),
+        InputKind::Reversed => (0..len as u128).rev().collect(),
+        InputKind::Random => {
+            // Xorshift64 with fixed seed.
+            let mut x: u64 = 0x2545_F491_4F6C_DD1D ^ len as u64;
+            (0..len)
+                .map(|_| {
+                    x ^= x << 13;
+                    x ^= x >> 7;
+                    x ^= x << 17;
+                    x as u128
+                })
+                .collect()
+        }
+        InputKind::Equal => vec![42; len],
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportRow {
+    pub algorithm: &'static str,
+    pub input: InputKind,
+    pub len: usize,
+    pub compares: usize,
+    pub swaps: usize,
+    pub moves: usize,
+    pub clones: usize,
+}
+
+// Count operations of Hoare and Lomuto quicksort, recursive quicksort and selection sort
+// on every kind of input of every length.
+pub fn complexity_report(lens: &[usize]) -> Vec<ReportRow> {
+    let mut rows: Vec<ReportRow> = Vec::new();
+    for &len in lens {
+        for input in [
+            InputKind::Sorted,
+            InputKind::Reversed,
+            InputKind::Random,
+            InputKind::Equal,
+        ] {
+            let v = gen_input(input, len);
+            let row = |algorithm: &'static str, counters: Counters| ReportRow {
+                algorithm,
+                input,
+                len,
+                compares: counters.compares.get(),
+                swaps: counters.swaps.get(),
+                moves: counters.moves.get(),
+                clones: counters.clones.get(),
+            };
+            rows.push(row("Hoare", count_partition(&v, Box::new(Hoare {}))));
+            rows.push(row("Lomuto", count_partition(&v, Box::new(Lomuto {}))));
+
+            // Recursive quicksort doesn't report compares, so wrapped elements count them.
+            let counters = Counters::default();
+            let wrapped = counters.wrap(v.clone());
+            quicksort_recursive_observed(wrapped, &Order::ascending(), &mut counters.observer());
+            rows.push(row("quicksort_recursive", counters));
+
+            let counters = Counters::default();
+            let mut res = v.clone();
+            selection_sort_observed(
+                &mut res,
+                Selection::Linear,
+                u128::cmp,
+                &mut counters.observer(),
+            );
+            rows.push(row("selection_sort", counters));
+        }
+    }
+    rows
+}
+
+fn count_partition(v: &[u128], partition: Box<dyn Partition<u128>>) -> Counters {
+    let counters = Counters::default();
+    let mut res = v.to_vec();
+    quicksort_observed(&mut res, u128::cmp, partition, &mut counters.observer());
+    counters
+}
+
+// Print report as table where counts can be compared with n log n and n^2.
+pub fn print_report(rows: &[ReportRow]) {
+    println!(
+        "{:<20} {:<9} {:>6} {:>9} {:>9} {:>9} {:>9} {:>9} {:>10}",
+        "algorithm", "input", "n", "compares", "swaps", "moves", "clones", "n log n", "n^2"
+    );
+    for row in rows {
+        let n = row.len as f64;
+        println!(
+            "{:<20} {:<9} {:>6} {:>9} {:>9} {:>9} {:>9} {:>9.0} {:>10}",
+            row.algorithm,
+            row.input.to_string(),
+            row.len,
+            row.compares,
+            row.swaps,
+            row.moves,
+            row.clones,
+            n * n.log2().max(0.0),
+            row.len * row.len
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::instrument::*;
+
+    #[test]
+    fn test_counters() {
+        let counters = Counters::default();
+        let mut v = counters.wrap(vec![3, 1, 2]);
+        let copy = v.clone();
+        v.sort();
+        assert_eq!(v.iter().map(|x| x.value).collect::<Vec<u128>>(), vec![1, 2, 3]);
+        assert_eq!(counters.clones.get(), copy.len());
+        assert!(counters.compares.get() >= 2);
+
+        let counters = Counters::default();
+        let mut v: Vec<u128> = vec![3, 1, 2];
+        v.sort_by(counters.comparator(u128::cmp));
+        assert!(counters.compares.get() >= 2);
+    }
+
+    #[test]
+    fn test_complexity_report() {
+        let lens: [usize; 3] = [16, 128, 512];
+        let rows = complexity_report(&lens);
+        print!("\n\n Complexity Report\n");
+        print_report(&rows);
+        assert_eq!(rows.len(), lens.len() * 4 * 4);
+
+        let find = |algorithm: &str, input: InputKind, len: usize| -> &ReportRow {
+            rows.iter()
+                .find(|r| r.algorithm == algorithm && r.input == input && r.len == len)
+                .unwrap()
+        };
+        for len in lens {
+            // Selection sort compares every other element of the rest with the current extremum.
+            let row = find("selection_sort", InputKind::Random, len);
+            assert_eq!(row.compares, len * (len - 1) / 2);
+            assert_eq!(row.clones, 0);
+            // Every pass swaps the minimum to the front, even if it's already there.
+            assert_eq!(row.swaps, len);
+            assert_eq!(row.moves, 0);
+            // Lomuto with the last element as pivot is quadratic on sorted input.
+            let row = find("Lomuto", InputKind::Sorted, len);
+            assert_eq!(row.compares, len * (len - 1) / 2);
+            assert!(row.swaps > 0);
+            assert_eq!(row.moves, 0);
+            // Recursive quicksort moves equal elements aside once and doesn't swap them.
+            let row = find("quicksort_recursive", InputKind::Equal, len);
+            assert_eq!(row.compares, len - 1);
+            assert_eq!((row.swaps, row.moves), (0, len - 1));
+            // Hoare with the middle element as pivot isn't.
+            let row = find("Hoare", InputKind::Sorted, len);
+            assert!(row.compares <= 2 * len * len.ilog2() as usize);
+        }
+    }
+}
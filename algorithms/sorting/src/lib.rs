@@ -0,0 +1,439 @@
+use std::cmp::Ordering;
+
+use quicksort::{quicksort, quicksort_recursive, Partition};
+use selection_sort::{selection_sort_by, Selection};
+
+mod external;
+mod heap;
+mod instrument;
+mod merge;
+mod radix;
+mod timsort;
+
+pub use external::ExternalSort;
+pub use heap::heapsort_by;
+pub use instrument::{
+    complexity_report, gen_input, print_report, Counted, Counters, InputKind, ReportRow,
+};
+pub use merge::{merge_sort_bottom_up_by, merge_sort_top_down_by};
+pub use order::{Direction, Order};
+pub use radix::{counting_sort, lsd_radix_sort, msd_radix_sort, RadixKey, COUNTING_MAX_RANGE};
+pub use timsort::timsort_by;
+
+// Common interface of all sorting algorithms, so they can be tested and compared together.
+pub trait Sorter<T> {
+    fn name(&self) -> &'static str;
+    // Stable sorter never changes relative order of equal elements, in both directions.
+    fn is_stable(&self) -> bool;
+    fn sort(&self, v: &mut [T], order: &Order<T>);
+}
+
+// Guaranteed stable sort, so multi-key sorts can be done by several passes
+// from the least important key to the most important one.
+pub fn stable_sort_by<T, F>(v: &mut [T], cmp: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    timsort_by(v, cmp)
+}
+
+pub fn stable_sort_by_key<T, K, F>(v: &mut [T], mut f: F)
+where
+    T: Clone,
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    stable_sort_by(v, |a, b| f(a).cmp(&f(b)))
+}
+
+pub struct TopDownMergeSort {}
+
+impl<T> Sorter<T> for TopDownMergeSort
+where
+    T: Clone,
+{
+    fn name(&self) -> &'static str {
+        "top-down merge sort"
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn sort(&self, v: &mut [T], order: &Order<T>) {
+        merge_sort_top_down_by(v, |a, b| order.compare(a, b))
+    }
+}
+
+pub struct BottomUpMergeSort {}
+
+impl<T> Sorter<T> for BottomUpMergeSort
+where
+    T: Clone,
+{
+    fn name(&self) -> &'static str {
+        "bottom-up merge sort"
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn sort(&self, v: &mut [T], order: &Order<T>) {
+        merge_sort_bottom_up_by(v, |a, b| order.compare(a, b))
+    }
+}
+
+pub struct HeapSort {}
+
+impl<T> Sorter<T> for HeapSort {
+    fn name(&self) -> &'static str {
+        "heapsort"
+    }
+
+    fn is_stable(&self) -> bool {
+        false
+    }
+
+    fn sort(&self, v: &mut [T], order: &Order<T>) {
+        heapsort_by(v, |a, b| order.compare(a, b))
+    }
+}
+
+// Radix sorts follow natural order only, which is expected to be the order of keys;
+// orders by key or comparator are sorted by timsort, so these sorters stay stable.
+pub struct LsdRadixSort {}
+
+impl<T> Sorter<T> for LsdRadixSort
+where
+    T: RadixKey + Clone,
+{
+    fn name(&self) -> &'static str {
+        "LSD radix sort"
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn sort(&self, v: &mut [T], order: &Order<T>) {
+        match order.direction() {
+            Some(direction) => lsd_radix_sort(v, direction),
+            None => timsort_by(v, |a, b| order.compare(a, b)),
+        }
+    }
+}
+
+pub struct MsdRadixSort {}
+
+impl<T> Sorter<T> for MsdRadixSort
+where
+    T: RadixKey + Clone,
+{
+    fn name(&self) -> &'static str {
+        "MSD radix sort"
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn sort(&self, v: &mut [T], order: &Order<T>) {
+        match order.direction() {
+            Some(direction) => msd_radix_sort(v, direction),
+            None => timsort_by(v, |a, b| order.compare(a, b)),
+        }
+    }
+}
+
+pub struct CountingSort {}
+
+impl<T> Sorter<T> for CountingSort
+where
+    T: RadixKey + Clone,
+{
+    fn name(&self) -> &'static str {
+        "counting sort"
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn sort(&self, v: &mut [T], order: &Order<T>) {
+        match order.direction() {
+            Some(direction) => counting_sort(v, direction),
+            None => timsort_by(v, |a, b| order.compare(a, b)),
+        }
+    }
+}
+
+pub struct TimSort {}
+
+impl<T> Sorter<T> for TimSort
+where
+    T: Clone,
+{
+    fn name(&self) -> &'static str {
+        "timsort"
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn sort(&self, v: &mut [T], order: &Order<T>) {
+        timsort_by(v, |a, b| order.compare(a, b))
+    }
+}
+
+// Quicksort with partition scheme; new partition is created for every sort.
+pub struct QuickSort<T> {
+    name: &'static str,
+    partition: fn() -> Box<dyn Partition<T>>,
+}
+
+impl<T> QuickSort<T> {
+    pub fn new(name: &'static str, partition: fn() -> Box<dyn Partition<T>>) -> Self {
+        Self { name, partition }
+    }
+}
+
+impl<T> Sorter<T> for QuickSort<T> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn is_stable(&self) -> bool {
+        false
+    }
+
+    fn sort(&self, v: &mut [T], order: &Order<T>) {
+        quicksort(v, order, (self.partition)())
+    }
+}
+
+pub struct QuickSortRecursive {}
+
+impl<T> Sorter<T> for QuickSortRecursive
+where
+    T: Clone,
+{
+    fn name(&self) -> &'static str {
+        "recursive quicksort"
+    }
+
+    fn is_stable(&self) -> bool {
+        false
+    }
+
+    fn sort(&self, v: &mut [T], order: &Order<T>) {
+        let sorted = quicksort_recursive(v.to_vec(), order);
+        v.clone_from_slice(&sorted);
+    }
+}
+
+pub struct SelectionSort {}
+
+impl<T> Sorter<T> for SelectionSort {
+    fn name(&self) -> &'static str {
+        "selection sort"
+    }
+
+    fn is_stable(&self) -> bool {
+        false
+    }
+
+    fn sort(&self, v: &mut [T], order: &Order<T>) {
+        selection_sort_by(v, Selection::Linear, |a, b| order.compare(a, b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quicksort::{Hoare, Introsort, Lomuto, ThreeWay};
+
+    use crate::*;
+
+    #[test]
+    fn test_sorters() -> Result<(), Box<dyn std::error::Error>> {
+        for sorter in gen_sorters::<u128>() {
+            for case in gen_cases() {
+                let mut res = case.0.clone();
+                sorter.sort(&mut res, &case.2.into());
+                if res != case.1 {
+                    return Err(format!("{}: {:?} -> {:?}", sorter.name(), case, res).into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_sorters_long() -> Result<(), Box<dyn std::error::Error>> {
+        let input: Vec<u128> = (0..2000).map(|i| (i * 7919 % 1009) as u128).collect();
+        let mut expected = input.clone();
+        expected.sort();
+        for sorter in gen_sorters::<u128>() {
+            let mut res = input.clone();
+            sorter.sort(&mut res, &Order::ascending());
+            if res != expected {
+                return Err(format!("{} failed", sorter.name()).into());
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_sorters_stability() -> Result<(), Box<dyn std::error::Error>> {
+        // Few distinct keys, so every key has many records with different indices.
+        let input: Vec<Record> = (0..500)
+            .map(|i| Record {
+                key: (i * 7919 % 13) as u8,
+                index: i,
+            })
+            .collect();
+        let mut unstable_noticed = false;
+        for sorter in gen_sorters::<Record>() {
+            for direction in [Direction::Ascending, Direction::Descending] {
+                let mut res = input.clone();
+                sorter.sort(&mut res, &direction.into());
+                let sorted =
+                    res.windows(2).all(|w| direction.apply(w[0].cmp(&w[1])) != Ordering::Greater);
+                if !sorted {
+                    return Err(format!("{} isn't sorted", sorter.name()).into());
+                }
+                let stable =
+                    res.windows(2).all(|w| w[0].key != w[1].key || w[0].index < w[1].index);
+                if sorter.is_stable() && !stable {
+                    return Err(format!("{} isn't stable", sorter.name()).into());
+                }
+                unstable_noticed |= !stable;
+            }
+        }
+        // Otherwise records don't show anything about stability.
+        assert!(unstable_noticed);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sorters_custom_order() -> Result<(), Box<dyn std::error::Error>> {
+        let input: Vec<u128> = (0..300).map(|i| (i * 7919 % 1009) as u128).collect();
+        // Last digit asc, value desc.
+        let order = Order::by_key(|x: &u128| x % 10).then(Order::descending());
+        let mut expected = input.clone();
+        expected.sort_by(|a, b| (a % 10).cmp(&(b % 10)).then(b.cmp(a)));
+        for sorter in gen_sorters::<u128>() {
+            let mut res = input.clone();
+            sorter.sort(&mut res, &order);
+            if res != expected {
+                return Err(format!("{} failed", sorter.name()).into());
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_stable_sort_multi_key() {
+        let mut people: Vec<(&str, u8)> = vec![
+            ("Bob", 30),
+            ("Alice", 25),
+            ("Carol", 30),
+            ("Alice", 20),
+            ("Bob", 25),
+        ];
+        // Sort by age descending and then by name: less important key goes first.
+        stable_sort_by_key(&mut people, |p| p.0);
+        stable_sort_by(&mut people, |a, b| b.1.cmp(&a.1));
+        assert_eq!(
+            people,
+            vec![
+                ("Bob", 30),
+                ("Carol", 30),
+                ("Alice", 25),
+                ("Bob", 25),
+                ("Alice", 20)
+            ]
+        );
+    }
+
+    // Records are compared by key only, index shows original position.
+    #[derive(Debug, Clone, Copy)]
+    struct Record {
+        key: u8,
+        index: usize,
+    }
+
+    impl PartialEq for Record {
+        fn eq(&self, other: &Self) -> bool {
+            self.key == other.key
+        }
+    }
+
+    impl Eq for Record {}
+
+    impl PartialOrd for Record {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Record {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.key.cmp(&other.key)
+        }
+    }
+
+    impl RadixKey for Record {
+        const BYTES: usize = 1;
+
+        fn key(&self) -> u128 {
+            self.key as u128
+        }
+    }
+
+    pub(crate) fn gen_sorters<T>() -> Vec<Box<dyn Sorter<T>>>
+    where
+        T: Ord + Clone + RadixKey + 'static,
+    {
+        vec![
+            Box::new(TopDownMergeSort {}),
+            Box::new(BottomUpMergeSort {}),
+            Box::new(HeapSort {}),
+            Box::new(LsdRadixSort {}),
+            Box::new(MsdRadixSort {}),
+            Box::new(CountingSort {}),
+            Box::new(TimSort {}),
+            Box::new(QuickSort::new("Hoare quicksort", || Box::new(Hoare {}))),
+            Box::new(QuickSort::new("Lomuto quicksort", || Box::new(Lomuto {}))),
+            Box::new(QuickSort::new("three-way quicksort", || Box::new(ThreeWay {}))),
+            Box::new(QuickSort::new("introsort", || Box::new(Introsort::new(Hoare {})))),
+            Box::new(QuickSortRecursive {}),
+            Box::new(SelectionSort {}),
+        ]
+    }
+
+    pub(crate) fn gen_cases() -> Vec<(Vec<u128>, Vec<u128>, Direction)> {
+        vec![
+            (vec![], vec![], Direction::Ascending),
+            (vec![7], vec![7], Direction::Descending),
+            (vec![4, 2, 0, 9, 2], vec![0, 2, 2, 4, 9], Direction::Ascending),
+            (
+                vec![55, 8, 12, 34, 5, 7, 122, 34, 0],
+                vec![0, 5, 7, 8, 12, 34, 34, 55, 122],
+                Direction::Ascending,
+            ),
+            (
+                vec![55, 8, 12, 5, 7, 122, 34, 0],
+                vec![122, 55, 34, 12, 8, 7, 5, 0],
+                Direction::Descending,
+            ),
+            (
+                vec![u128::MAX, 0, u128::MAX, 1],
+                vec![0, 1, u128::MAX, u128::MAX],
+                Direction::Ascending,
+            ),
+        ]
+    }
+}
@@ -0,0 +1,93 @@
+use std::cmp::Ordering;
+
+pub fn merge_sort_top_down_by<T, F>(v: &mut [T], mut cmp: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut buf: Vec<T> = Vec::with_capacity(v.len() / 2);
+    top_down(v, &mut buf, &mut cmp);
+}
+
+fn top_down<T, F>(v: &mut [T], buf: &mut Vec<T>, cmp: &mut F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if v.len() < 2 {
+        return;
+    }
+    let mid = v.len() / 2;
+    top_down(&mut v[..mid], buf, cmp);
+    top_down(&mut v[mid..], buf, cmp);
+    merge(v, mid, buf, cmp);
+}
+
+// Merge neighbor runs of width 1, 2, 4 and so on without recursion.
+pub fn merge_sort_bottom_up_by<T, F>(v: &mut [T], mut cmp: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut buf: Vec<T> = Vec::with_capacity(v.len() / 2);
+    let mut width: usize = 1;
+    while width < v.len() {
+        for start in (0..v.len()).step_by(2 * width) {
+            let mid = (start + width).min(v.len());
+            let stop = (start + 2 * width).min(v.len());
+            if mid < stop {
+                merge(&mut v[start..stop], mid - start, &mut buf, &mut cmp);
+            }
+        }
+        width *= 2;
+    }
+}
+
+// Merge sorted [0, mid) and [mid, len) runs of v.
+// Left run is copied to buf and merged back from the beginning, so writes never overtake right run.
+// Equal elements are taken from the left run first, so merge is stable.
+pub(crate) fn merge<T, F>(v: &mut [T], mid: usize, buf: &mut Vec<T>, cmp: &mut F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    buf.clear();
+    buf.extend_from_slice(&v[..mid]);
+    let (mut i, mut j, mut k) = (0, mid, 0);
+    while i < buf.len() && j < v.len() {
+        if cmp(&v[j], &buf[i]) == Ordering::Less {
+            v[k] = v[j].clone();
+            j += 1;
+        } else {
+            v[k] = buf[i].clone();
+            i += 1;
+        }
+        k += 1;
+    }
+    while i < buf.len() {
+        v[k] = buf[i].clone();
+        i += 1;
+        k += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::merge::*;
+
+    #[test]
+    fn test_merge_sort() {
+        for n in [0, 1, 2, 3, 7, 8, 9, 100, 1000] {
+            let input: Vec<u128> = (0..n).map(|i| (i * 7919 % 1009) as u128).collect();
+            let mut expected = input.clone();
+            expected.sort();
+
+            let mut res = input.clone();
+            merge_sort_top_down_by(&mut res, u128::cmp);
+            assert_eq!(res, expected);
+            let mut res = input.clone();
+            merge_sort_bottom_up_by(&mut res, u128::cmp);
+            assert_eq!(res, expected);
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use std::cmp::Ordering;
+
+use order::Direction;
+
+// Keys range bigger than this is sorted by lsd_radix_sort instead of allocating huge counts table.
+pub const COUNTING_MAX_RANGE: u128 = 1 << 20;
+
+// Ranges not longer than this are finished by insertion sort in msd_radix_sort.
+const MSD_INSERTION_THRESHOLD: usize = 32;
+
+// Unsigned integer key which radix and counting sorts work with.
+pub trait RadixKey {
+    // Number of significant bytes of key.
+    const BYTES: usize;
+
+    fn key(&self) -> u128;
+}
+
+macro_rules! impl_radix_key {
+    ($($t:ty),*) => {
+        $(
+            impl RadixKey for $t {
+                const BYTES: usize = std::mem::size_of::<$t>();
+
+                fn key(&self) -> u128 {
+                    *self as u128
+                }
+            }
+        )*
+    };
+}
+
+impl_radix_key!(u8, u16, u32, u64, u128, usize);
+
+// Bucket of element by i-th byte of its key (0 is the least significant one).
+// Buckets go in reverse order if descending.
+fn bucket<T>(x: &T, i: usize, direction: Direction) -> usize
+where
+    T: RadixKey,
+{
+    let byte = ((x.key() >> (8 * i)) & 0xff) as usize;
+    match direction {
+        Direction::Ascending => byte,
+        Direction::Descending => 255 - byte,
+    }
+}
+
+// Start of every bucket in sorted order by i-th byte; the last item is the length of v.
+fn bucket_starts<T>(v: &[T], i: usize, direction: Direction) -> [usize; 257]
+where
+    T: RadixKey,
+{
+    let mut starts = [0; 257];
+    for x in v {
+        starts[bucket(x, i, direction) + 1] += 1;
+    }
+    for b in 1..starts.len() {
+        starts[b] += starts[b - 1];
+    }
+    starts
+}
+
+// Stable counting sort by every byte from the least significant one.
+pub fn lsd_radix_sort<T>(v: &mut [T], direction: Direction)
+where
+    T: RadixKey + Clone,
+{
+    let mut buf: Vec<T> = v.to_vec();
+    for i in 0..T::BYTES {
+        let mut starts = bucket_starts(v, i, direction);
+        // All keys have the same byte, so pass doesn't move anything.
+        if starts.windows(2).any(|w| w[1] - w[0] == v.len()) {
+            continue;
+        }
+        for x in v.iter() {
+            let b = bucket(x, i, direction);
+            buf[starts[b]] = x.clone();
+            starts[b] += 1;
+        }
+        v.clone_from_slice(&buf);
+    }
+}
+
+// Split into buckets by the most significant byte and sort every bucket by the next bytes.
+pub fn msd_radix_sort<T>(v: &mut [T], direction: Direction)
+where
+    T: RadixKey + Clone,
+{
+    let mut buf: Vec<T> = Vec::with_capacity(v.len());
+    msd(v, T::BYTES - 1, direction, &mut buf);
+}
+
+fn msd<T>(v: &mut [T], i: usize, direction: Direction, buf: &mut Vec<T>)
+where
+    T: RadixKey + Clone,
+{
+    if v.len() <= MSD_INSERTION_THRESHOLD {
+        insertion_sort(v, direction);
+        return;
+    }
+    let starts = bucket_starts(v, i, direction);
+    buf.clear();
+    buf.extend_from_slice(v);
+    let mut next = starts;
+    for x in buf.iter() {
+        let b = bucket(x, i, direction);
+        v[next[b]] = x.clone();
+        next[b] += 1;
+    }
+    if i == 0 {
+        return;
+    }
+    for b in 0..256 {
+        if starts[b + 1] - starts[b] > 1 {
+            msd(&mut v[starts[b]..starts[b + 1]], i - 1, direction, buf);
+        }
+    }
+}
+
+// Stable insertion sort by whole key.
+fn insertion_sort<T>(v: &mut [T], direction: Direction)
+where
+    T: RadixKey,
+{
+    let before = |a: &T, b: &T| direction.apply(a.key().cmp(&b.key())) == Ordering::Less;
+    for i in 1..v.len() {
+        let mut j = i;
+        while j > 0 && before(&v[j], &v[j - 1]) {
+            v.swap(j - 1, j);
+            j -= 1;
+        }
+    }
+}
+
+// Stable counting sort by whole key, for keys in small range only.
+// Falls back to lsd_radix_sort if range of keys is bigger than COUNTING_MAX_RANGE.
+pub fn counting_sort<T>(v: &mut [T], direction: Direction)
+where
+    T: RadixKey + Clone,
+{
+    let (Some(min), Some(max)) = (v.iter().map(T::key).min(), v.iter().map(T::key).max()) else {
+        return;
+    };
+    if max - min >= COUNTING_MAX_RANGE {
+        return lsd_radix_sort(v, direction);
+    }
+    let range = (max - min + 1) as usize;
+    let index = |x: &T| -> usize {
+        let i = (x.key() - min) as usize;
+        match direction {
+            Direction::Ascending => i,
+            Direction::Descending => range - 1 - i,
+        }
+    };
+    let mut starts: Vec<usize> = vec![0; range + 1];
+    for x in v.iter() {
+        starts[index(x) + 1] += 1;
+    }
+    for i in 1..starts.len() {
+        starts[i] += starts[i - 1];
+    }
+    let buf: Vec<T> = v.to_vec();
+    for x in buf {
+        let i = index(&x);
+        v[starts[i]] = x;
+        starts[i] += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::radix::*;
+
+    #[test]
+    fn test_radix_sort() {
+        for n in [0, 1, 2, 31, 32, 33, 100, 1000] {
+            let input: Vec<u64> =
+                (0..n).map(|i: u64| i.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> (i % 64)).collect();
+            let mut expected = input.clone();
+            expected.sort();
+            let sorters: Vec<fn(&mut [u64], Direction)> =
+                vec![lsd_radix_sort, msd_radix_sort, counting_sort];
+            for sort in sorters {
+                let mut res = input.clone();
+                sort(&mut res, Direction::Ascending);
+                assert_eq!(res, expected);
+                sort(&mut res, Direction::Descending);
+                assert_eq!(res, expected.iter().rev().cloned().collect::<Vec<u64>>());
+            }
+        }
+    }
+
+    #[test]
+    fn test_counting_sort_small_range() {
+        let input: Vec<u8> = (0..1000).map(|i| (i * 31 % 7 + 100) as u8).collect();
+        let mut expected = input.clone();
+        expected.sort();
+        let mut res = input.clone();
+        counting_sort(&mut res, Direction::Ascending);
+        assert_eq!(res, expected);
+        expected.reverse();
+        counting_sort(&mut res, Direction::Descending);
+        assert_eq!(res, expected);
+    }
+}
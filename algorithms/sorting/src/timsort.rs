@@ -0,0 +1,158 @@
+use std::cmp::Ordering;
+
+use crate::merge::merge;
+
+// Runs shorter than min_run are extended by binary insertion sort;
+// inputs shorter than this are sorted by insertion sort alone.
+const MIN_MERGE: usize = 32;
+
+// Find existing ascending and strictly descending runs and merge them with merge sort.
+// Already sorted or reversed input is sorted in linear time.
+// Merge uses plain merge without galloping.
+pub fn timsort_by<T, F>(v: &mut [T], mut cmp: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let min_run = min_run(v.len());
+    // Start and length of runs which aren't merged yet.
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    let mut buf: Vec<T> = Vec::new();
+    let mut start: usize = 0;
+    while start < v.len() {
+        let mut len = count_run(&mut v[start..], &mut cmp);
+        if len < min_run {
+            let forced = min_run.min(v.len() - start);
+            binary_insertion_sort(&mut v[start..start + forced], len, &mut cmp);
+            len = forced;
+        }
+        runs.push((start, len));
+        collapse(v, &mut runs, &mut buf, &mut cmp, false);
+        start += len;
+    }
+    collapse(v, &mut runs, &mut buf, &mut cmp, true);
+}
+
+// Length in [MIN_MERGE / 2, MIN_MERGE] range such that len / min_run is close to power of two.
+fn min_run(mut len: usize) -> usize {
+    let mut rest: usize = 0;
+    while len >= MIN_MERGE {
+        rest |= len & 1;
+        len >>= 1;
+    }
+    len + rest
+}
+
+// Length of the run at the beginning of v.
+// Descending run has to be strictly descending, so reversing it keeps sort stable.
+fn count_run<T, F>(v: &mut [T], cmp: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if v.len() < 2 {
+        return v.len();
+    }
+    let mut end: usize = 2;
+    if cmp(&v[1], &v[0]) == Ordering::Less {
+        while end < v.len() && cmp(&v[end], &v[end - 1]) == Ordering::Less {
+            end += 1;
+        }
+        v[..end].reverse();
+    } else {
+        while end < v.len() && cmp(&v[end], &v[end - 1]) != Ordering::Less {
+            end += 1;
+        }
+    }
+    end
+}
+
+// First sorted elements of v are already sorted, the rest are inserted one by one.
+// Element is inserted after equal ones to keep sort stable.
+fn binary_insertion_sort<T, F>(v: &mut [T], sorted: usize, cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in sorted.max(1)..v.len() {
+        let (head, tail) = v.split_at(i);
+        let pos = head.partition_point(|x| cmp(x, &tail[0]) != Ordering::Greater);
+        v[pos..=i].rotate_right(1);
+    }
+}
+
+// Merge runs on top of the stack until every run is longer than the next two together
+// and than the next one; if force, merge everything into one run.
+fn collapse<T, F>(
+    v: &mut [T],
+    runs: &mut Vec<(usize, usize)>,
+    buf: &mut Vec<T>,
+    cmp: &mut F,
+    force: bool,
+) where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    while runs.len() > 1 {
+        let n = runs.len();
+        let len = |i: usize| runs[i].1;
+        let broken = (n >= 3 && len(n - 3) <= len(n - 2) + len(n - 1))
+            || (n >= 4 && len(n - 4) <= len(n - 3) + len(n - 2));
+        let at = if broken && len(n - 3) < len(n - 1) {
+            n - 3
+        } else if broken || force || len(n - 2) <= len(n - 1) {
+            n - 2
+        } else {
+            break;
+        };
+        let (start, left) = runs[at];
+        let right = runs[at + 1].1;
+        merge(&mut v[start..start + left + right], left, buf, cmp);
+        runs[at] = (start, left + right);
+        runs.remove(at + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::timsort::*;
+
+    #[test]
+    fn test_timsort() {
+        for n in [0, 1, 2, 31, 32, 33, 64, 100, 1000, 5000] {
+            let input: Vec<u128> = (0..n).map(|i| (i * 7919 % 1009) as u128).collect();
+            let mut expected = input.clone();
+            expected.sort();
+            let mut res = input.clone();
+            timsort_by(&mut res, u128::cmp);
+            assert_eq!(res, expected);
+        }
+    }
+
+    #[test]
+    fn test_timsort_runs() {
+        // Sorted and reversed inputs are single runs and need n - 1 comparisons.
+        let input: Vec<u128> = (0..1000).collect();
+        for asc in [true, false] {
+            let mut compares: usize = 0;
+            let mut res = input.clone();
+            timsort_by(&mut res, |a, b| {
+                compares += 1;
+                if asc {
+                    a.cmp(b)
+                } else {
+                    b.cmp(a)
+                }
+            });
+            assert_eq!(compares, input.len() - 1);
+            assert!(res.windows(2).all(|w| if asc { w[0] < w[1] } else { w[0] > w[1] }));
+        }
+
+        // Ascending runs with descending pieces in between.
+        let input: Vec<u128> =
+            (0..2000).map(|i| if i / 100 % 2 == 0 { i } else { 3000 - i }).collect();
+        let mut expected = input.clone();
+        expected.sort();
+        let mut res = input.clone();
+        timsort_by(&mut res, u128::cmp);
+        assert_eq!(res, expected);
+    }
+}